@@ -0,0 +1,485 @@
+use crate::error::*;
+use crate::{WaveFormat, WAVEFORMAT_ID_IMA_ADPCM, WAVEFORMAT_ID_MS_ADPCM};
+
+/// Step sizes of IMA ADPCM.
+const IMA_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66, 73, 80, 88, 97, 107, 118, 130,
+    143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449, 494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282,
+    1411, 1552, 1707, 1878, 2066, 2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+/// Step index changes of IMA ADPCM by the magnitude of the code.
+const IMA_INDEX_TABLE: [i32; 8] = [-1, -1, -1, -1, 2, 4, 6, 8];
+
+/// Standard predictor coefficients of MS ADPCM. (coefficient of the previous sample, and the one before)
+const MS_COEFFICIENT_TABLE: [[i32; 2]; 7] = [[256, 0], [512, -256], [0, 0], [192, 64], [240, 0], [460, -208], [392, -232]];
+/// Delta changes of MS ADPCM by the code. (x / 256)
+const MS_ADAPTATION_TABLE: [i32; 16] = [230, 230, 230, 230, 307, 409, 512, 614, 768, 614, 512, 409, 307, 230, 230, 230];
+/// Minimum delta of MS ADPCM.
+const MS_DELTA_MIN: i32 = 16;
+/// Maximum delta of MS ADPCM to avoid overflow.
+const MS_DELTA_MAX: i32 = i32::MAX / 768;
+
+/// Check the format id is ADPCM, which is encoded block by block.
+pub(crate) fn is_adpcm_format(format_id: usize) -> bool {
+    format_id == WAVEFORMAT_ID_IMA_ADPCM || format_id == WAVEFORMAT_ID_MS_ADPCM
+}
+
+/// Block layout of ADPCM formats.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct AdpcmFormat {
+    pub(crate) wave_format: WaveFormat,
+    /// Bytes per block of all channels.
+    pub(crate) block_align: usize,
+    /// Samples per block of each channel.
+    pub(crate) samples_per_block: usize,
+    /// Predictor coefficients of MS ADPCM. Empty for IMA ADPCM.
+    pub(crate) coefficient_vec: Vec<[i32; 2]>,
+}
+
+impl AdpcmFormat {
+    /// Make the block layout. If the block align is None, it is 256 bytes per channel for each 11025Hz of the sampling rate.
+    pub(crate) fn new(wave_format: &WaveFormat, op_block_align: Option<usize>) -> Result<AdpcmFormat> {
+        WaveFormat::format_check(wave_format)?;
+        let block_align = op_block_align.unwrap_or(256 * wave_format.channel * (wave_format.sampling_rate / 11025).max(1));
+        let coefficient_vec = if wave_format.id == WAVEFORMAT_ID_MS_ADPCM {
+            MS_COEFFICIENT_TABLE.to_vec()
+        } else {
+            Vec::new()
+        };
+        Self::layout_check(wave_format, block_align, coefficient_vec)
+    }
+
+    /// Get the block layout from "fmt" chunk body.
+    pub(crate) fn from_format_chunk(wave_format: &WaveFormat, chunk_body: &[u8]) -> Result<AdpcmFormat> {
+        // 20 = WAVEFORMATEX + Samples Per Block
+        if chunk_body.len() < 20 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"fmt\"".to_string()),
+            ));
+        }
+        // Block Size
+        let block_align = usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x0c..0x0e])?));
+        let mut coefficient_vec: Vec<[i32; 2]> = Vec::new();
+        if wave_format.id == WAVEFORMAT_ID_MS_ADPCM {
+            // Number Of Coefficients
+            let coefficient_len = match chunk_body.get(0x14..0x16) {
+                Some(bytes) => usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(bytes)?)),
+                None => 0,
+            };
+            if coefficient_len == 0 || chunk_body.len() < 0x16 + 4 * coefficient_len {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::SubChunkSizeError,
+                    Some("\"fmt\"".to_string()),
+                ));
+            }
+            // Coefficients
+            for bytes in chunk_body[0x16..0x16 + 4 * coefficient_len].chunks(4) {
+                coefficient_vec.push([
+                    i32::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+                    i32::from(i16::from_le_bytes([bytes[2], bytes[3]])),
+                ]);
+            }
+        }
+        let mut adpcm_format = Self::layout_check(wave_format, block_align, coefficient_vec)?;
+        // Samples Per Block. The block may have fewer samples than it can hold.
+        let samples_per_block = usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x12..0x14])?));
+        if samples_per_block < Self::header_samples(wave_format.id) || samples_per_block > adpcm_format.samples_per_block {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("samples per block".to_string()),
+            ));
+        }
+        adpcm_format.samples_per_block = samples_per_block;
+        Ok(adpcm_format)
+    }
+
+    fn layout_check(wave_format: &WaveFormat, block_align: usize, coefficient_vec: Vec<[i32; 2]>) -> Result<AdpcmFormat> {
+        if wave_format.bits != 4 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("bit rate".to_string()),
+            ));
+        }
+        let channel = wave_format.channel;
+        // Header bytes and the number of codes in a group.
+        // IMA: 4 bytes header, and 4 bytes (8 codes) of each channel in turn.
+        // MS: 7 bytes header, and a code of each channel in turn.
+        let (header_size, group_size) = match wave_format.id {
+            WAVEFORMAT_ID_IMA_ADPCM => (4 * channel, 8 * channel),
+            _ => (7 * channel, channel),
+        };
+        if block_align < header_size || !(2 * (block_align - header_size)).is_multiple_of(group_size) || block_align > 0xffff {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("block size".to_string()),
+            ));
+        }
+        let samples_per_block = (block_align - header_size) * 2 / channel + Self::header_samples(wave_format.id);
+        if samples_per_block > 0xffff {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("samples per block".to_string()),
+            ));
+        }
+        Ok(AdpcmFormat {
+            wave_format: *wave_format,
+            block_align,
+            samples_per_block,
+            coefficient_vec,
+        })
+    }
+
+    /// Number of samples in the block header of each channel.
+    fn header_samples(format_id: usize) -> usize {
+        match format_id {
+            WAVEFORMAT_ID_IMA_ADPCM => 1,
+            _ => 2,
+        }
+    }
+
+    /// Make "fmt" chunk body.
+    pub(crate) fn to_format_chunk(&self) -> Vec<u8> {
+        let mut chunk_body: Vec<u8> = Vec::new();
+        // format id
+        chunk_body.append(&mut self.wave_format.id.to_le_bytes()[0..2].to_vec());
+        // channel
+        chunk_body.append(&mut self.wave_format.channel.to_le_bytes()[0..2].to_vec());
+        // Sampling Rate
+        chunk_body.append(&mut self.wave_format.sampling_rate.to_le_bytes()[0..4].to_vec());
+        // Byte Per Sec
+        chunk_body.append(
+            &mut (self.wave_format.sampling_rate * self.block_align / self.samples_per_block).to_le_bytes()[0..4].to_vec(),
+        );
+        // Block Size
+        chunk_body.append(&mut self.block_align.to_le_bytes()[0..2].to_vec());
+        // Bit Rate
+        chunk_body.append(&mut self.wave_format.bits.to_le_bytes()[0..2].to_vec());
+        // Extension Size
+        let extension_size = if self.coefficient_vec.is_empty() {
+            2
+        } else {
+            // Samples Per Block + Number Of Coefficients + Coefficients
+            4 + 4 * self.coefficient_vec.len()
+        };
+        chunk_body.append(&mut extension_size.to_le_bytes()[0..2].to_vec());
+        // Samples Per Block
+        chunk_body.append(&mut self.samples_per_block.to_le_bytes()[0..2].to_vec());
+        if !self.coefficient_vec.is_empty() {
+            // Number Of Coefficients
+            chunk_body.append(&mut self.coefficient_vec.len().to_le_bytes()[0..2].to_vec());
+            // Coefficients
+            for coefficient in &self.coefficient_vec {
+                chunk_body.append(&mut (coefficient[0] as i16).to_le_bytes().to_vec());
+                chunk_body.append(&mut (coefficient[1] as i16).to_le_bytes().to_vec());
+            }
+        }
+        chunk_body
+    }
+
+    /// Decode blocks to 16bit PCM. Return Value: Outer is channel vec. Inner is data vec.
+    /// The last block may be shorter than the block align.
+    pub(crate) fn decode(&self, bytes_data_vec: &[u8]) -> Result<Vec<Vec<i16>>> {
+        let mut channel_pcm_vec: Vec<Vec<i16>> = vec![Vec::new(); self.wave_format.channel];
+        for block in bytes_data_vec.chunks(self.block_align) {
+            let mut block_pcm_vec = match self.wave_format.id {
+                WAVEFORMAT_ID_IMA_ADPCM => self.decode_ima_block(block)?,
+                _ => self.decode_ms_block(block)?,
+            };
+            for (pcm_vec, block_pcm) in channel_pcm_vec.iter_mut().zip(&mut block_pcm_vec) {
+                block_pcm.truncate(self.samples_per_block);
+                pcm_vec.append(block_pcm);
+            }
+        }
+        Ok(channel_pcm_vec)
+    }
+
+    fn decode_ima_block(&self, block: &[u8]) -> Result<Vec<Vec<i16>>> {
+        let channel = self.wave_format.channel;
+        if block.len() < 4 * channel {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"data\"".to_string()),
+            ));
+        }
+        let mut block_pcm_vec: Vec<Vec<i16>> = vec![Vec::new(); channel];
+        let mut state_vec: Vec<ImaState> = Vec::new();
+        for (channel_idx, header) in block[0..4 * channel].chunks(4).enumerate() {
+            let state = ImaState {
+                predictor: i32::from(i16::from_le_bytes([header[0], header[1]])),
+                step_idx: i32::from(header[2]).min(88),
+            };
+            block_pcm_vec[channel_idx].push(state.predictor as i16);
+            state_vec.push(state);
+        }
+        // Incomplete group of 8 samples is ignored.
+        for group in block[4 * channel..].chunks_exact(4 * channel) {
+            for (channel_idx, channel_group) in group.chunks(4).enumerate() {
+                for byte_data in channel_group {
+                    for code in [byte_data & 0x0f, byte_data >> 4] {
+                        block_pcm_vec[channel_idx].push(state_vec[channel_idx].decode(code));
+                    }
+                }
+            }
+        }
+        Ok(block_pcm_vec)
+    }
+
+    fn decode_ms_block(&self, block: &[u8]) -> Result<Vec<Vec<i16>>> {
+        let channel = self.wave_format.channel;
+        if block.len() < 7 * channel {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"data\"".to_string()),
+            ));
+        }
+        // Header fields are interleaved by channel.
+        let header_field = |field_head: usize, channel_idx: usize| -> i32 {
+            let pos = field_head + 2 * channel_idx;
+            i32::from(i16::from_le_bytes([block[pos], block[pos + 1]]))
+        };
+        let mut block_pcm_vec: Vec<Vec<i16>> = vec![Vec::new(); channel];
+        let mut state_vec: Vec<MsState> = Vec::new();
+        for (channel_idx, block_pcm) in block_pcm_vec.iter_mut().enumerate() {
+            let coefficient = match self.coefficient_vec.get(usize::from(block[channel_idx])) {
+                Some(coefficient) => *coefficient,
+                None => {
+                    return Err(WavF64VecError::new(
+                        WavF64VecErrorKind::SubChunkSizeError,
+                        Some("predictor index".to_string()),
+                    ));
+                }
+            };
+            let state = MsState {
+                coefficient,
+                delta: header_field(channel, channel_idx),
+                sample1: header_field(3 * channel, channel_idx),
+                sample2: header_field(5 * channel, channel_idx),
+            };
+            // The older sample is first.
+            block_pcm.push(state.sample2 as i16);
+            block_pcm.push(state.sample1 as i16);
+            state_vec.push(state);
+        }
+        // The high nibble is first.
+        let code_vec: Vec<u8> = block[7 * channel..]
+            .iter()
+            .flat_map(|byte_data| [byte_data >> 4, byte_data & 0x0f])
+            .collect();
+        // Incomplete frame is ignored.
+        for frame in code_vec.chunks_exact(channel) {
+            for (channel_idx, code) in frame.iter().enumerate() {
+                block_pcm_vec[channel_idx].push(state_vec[channel_idx].decode(*code));
+            }
+        }
+        Ok(block_pcm_vec)
+    }
+
+    /// Encode 16bit PCM to blocks. Parameters: Outer is channel vec. Inner is data vec.
+    /// The last block is filled with the last sample.
+    pub(crate) fn encode(&self, channel_pcm_vec: &[Vec<i16>]) -> Vec<u8> {
+        let channel = self.wave_format.channel;
+        let data_len = channel_pcm_vec[0].len();
+        let mut bytes_data_vec: Vec<u8> = Vec::new();
+        // The step index (IMA) and the delta (MS) are carried over to the next block.
+        let mut ima_state_vec = vec![
+            ImaState {
+                predictor: 0,
+                step_idx: 0,
+            };
+            channel
+        ];
+        let mut ms_delta_vec = vec![MS_DELTA_MIN; channel];
+        for block_head in (0..data_len).step_by(self.samples_per_block) {
+            let block_pcm_vec: Vec<Vec<i32>> = channel_pcm_vec
+                .iter()
+                .map(|pcm_vec| {
+                    (block_head..block_head + self.samples_per_block)
+                        .map(|data_idx| i32::from(pcm_vec[data_idx.min(data_len - 1)]))
+                        .collect()
+                })
+                .collect();
+            match self.wave_format.id {
+                WAVEFORMAT_ID_IMA_ADPCM => Self::encode_ima_block(&block_pcm_vec, &mut ima_state_vec, &mut bytes_data_vec),
+                _ => self.encode_ms_block(&block_pcm_vec, &mut ms_delta_vec, &mut bytes_data_vec),
+            }
+        }
+        bytes_data_vec
+    }
+
+    fn encode_ima_block(block_pcm_vec: &[Vec<i32>], state_vec: &mut [ImaState], bytes_data_vec: &mut Vec<u8>) {
+        for (state, block_pcm) in state_vec.iter_mut().zip(block_pcm_vec) {
+            state.predictor = block_pcm[0];
+            bytes_data_vec.append(&mut (state.predictor as i16).to_le_bytes().to_vec());
+            bytes_data_vec.append(&mut vec![state.step_idx as u8, 0]);
+        }
+        for group_head in (1..block_pcm_vec[0].len()).step_by(8) {
+            for (state, block_pcm) in state_vec.iter_mut().zip(block_pcm_vec) {
+                for pcm_pair in block_pcm[group_head..group_head + 8].chunks(2) {
+                    let low_code = state.encode(pcm_pair[0]);
+                    let high_code = state.encode(pcm_pair[1]);
+                    bytes_data_vec.push(low_code | (high_code << 4));
+                }
+            }
+        }
+    }
+
+    /// The predictor of each channel is the one with the least error in the block.
+    fn encode_ms_block(&self, block_pcm_vec: &[Vec<i32>], delta_vec: &mut [i32], bytes_data_vec: &mut Vec<u8>) {
+        let channel = self.wave_format.channel;
+        let mut predictor_idx_vec: Vec<usize> = Vec::new();
+        let mut state_vec: Vec<MsState> = Vec::new();
+        let mut channel_code_vec: Vec<Vec<u8>> = Vec::new();
+        for (block_pcm, delta) in block_pcm_vec.iter().zip(delta_vec.iter_mut()) {
+            // Delta is a 16bit field of the header.
+            *delta = (*delta).min(i16::MAX as i32);
+            let mut op_best: Option<(i64, usize, MsState, Vec<u8>)> = None;
+            for (predictor_idx, coefficient) in self.coefficient_vec.iter().enumerate() {
+                let state = MsState {
+                    coefficient: *coefficient,
+                    delta: *delta,
+                    sample1: block_pcm[1],
+                    sample2: block_pcm[0],
+                };
+                let mut trial_state = state;
+                let mut error_power: i64 = 0;
+                let code_vec: Vec<u8> = block_pcm[2..]
+                    .iter()
+                    .map(|pcm_val| {
+                        let code = trial_state.encode(*pcm_val);
+                        error_power += i64::from(trial_state.sample1 - pcm_val).pow(2);
+                        code
+                    })
+                    .collect();
+                if op_best.as_ref().is_none_or(|best| error_power < best.0) {
+                    op_best = Some((error_power, predictor_idx, state, code_vec));
+                }
+            }
+            // The coefficients are not empty.
+            let (_, predictor_idx, state, code_vec) = op_best.unwrap();
+            predictor_idx_vec.push(predictor_idx);
+            state_vec.push(state);
+            channel_code_vec.push(code_vec);
+        }
+
+        // Header
+        for predictor_idx in &predictor_idx_vec {
+            bytes_data_vec.push(*predictor_idx as u8);
+        }
+        for state in &state_vec {
+            bytes_data_vec.append(&mut (state.delta as i16).to_le_bytes().to_vec());
+        }
+        for state in &state_vec {
+            bytes_data_vec.append(&mut (state.sample1 as i16).to_le_bytes().to_vec());
+        }
+        for state in &state_vec {
+            bytes_data_vec.append(&mut (state.sample2 as i16).to_le_bytes().to_vec());
+        }
+        // Codes of the interleaved samples. The high nibble is first.
+        let code_vec: Vec<u8> = (0..channel_code_vec[0].len() * channel)
+            .map(|code_idx| channel_code_vec[code_idx % channel][code_idx / channel])
+            .collect();
+        for code_pair in code_vec.chunks(2) {
+            bytes_data_vec.push((code_pair[0] << 4) | code_pair[1]);
+        }
+
+        // Delta after the block
+        for ((delta, state), code_vec) in delta_vec.iter_mut().zip(&mut state_vec).zip(&channel_code_vec) {
+            for code in code_vec {
+                state.decode(*code);
+            }
+            *delta = state.delta;
+        }
+    }
+}
+
+/// Predictor and step index of a channel of IMA ADPCM.
+#[derive(Clone, Copy)]
+struct ImaState {
+    predictor: i32,
+    step_idx: i32,
+}
+
+impl ImaState {
+    fn decode(&mut self, code: u8) -> i16 {
+        let code = i32::from(code);
+        let step = IMA_STEP_TABLE[self.step_idx as usize];
+        let mut diff = step >> 3;
+        if code & 4 != 0 {
+            diff += step;
+        }
+        if code & 2 != 0 {
+            diff += step >> 1;
+        }
+        if code & 1 != 0 {
+            diff += step >> 2;
+        }
+        if code & 8 != 0 {
+            diff = -diff;
+        }
+        self.predictor = (self.predictor + diff).clamp(i16::MIN as i32, i16::MAX as i32);
+        self.step_idx = (self.step_idx + IMA_INDEX_TABLE[(code & 7) as usize]).clamp(0, 88);
+        self.predictor as i16
+    }
+
+    fn encode(&mut self, sample: i32) -> u8 {
+        let mut step = IMA_STEP_TABLE[self.step_idx as usize];
+        let mut diff = sample - self.predictor;
+        let mut code = 0u8;
+        if diff < 0 {
+            code = 8;
+            diff = -diff;
+        }
+        for bit in [4, 2, 1] {
+            if diff >= step {
+                code |= bit;
+                diff -= step;
+            }
+            step >>= 1;
+        }
+        // Same as the decoder.
+        self.decode(code);
+        code
+    }
+}
+
+/// Predictor coefficients, delta and the last 2 samples of a channel of MS ADPCM.
+#[derive(Clone, Copy)]
+struct MsState {
+    coefficient: [i32; 2],
+    delta: i32,
+    sample1: i32,
+    sample2: i32,
+}
+
+impl MsState {
+    fn predict(&self) -> i32 {
+        // Coefficients of "fmt" chunk are not limited, so the products may exceed 32bit.
+        let prediction = (i64::from(self.sample1) * i64::from(self.coefficient[0])
+            + i64::from(self.sample2) * i64::from(self.coefficient[1]))
+            / 256;
+        prediction.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+    }
+
+    fn decode(&mut self, code: u8) -> i16 {
+        // 4bit signed
+        let signed_code = (i32::from(code) << 28) >> 28;
+        let sample = (self.predict() + signed_code * self.delta).clamp(i16::MIN as i32, i16::MAX as i32);
+        self.sample2 = self.sample1;
+        self.sample1 = sample;
+        self.delta = (MS_ADAPTATION_TABLE[usize::from(code)] * self.delta / 256).clamp(MS_DELTA_MIN, MS_DELTA_MAX);
+        sample as i16
+    }
+
+    fn encode(&mut self, sample: i32) -> u8 {
+        let diff = sample - self.predict();
+        // Round to nearest
+        let bias = if diff >= 0 { self.delta / 2 } else { -self.delta / 2 };
+        let signed_code = ((diff + bias) / self.delta).clamp(-8, 7);
+        let code = (signed_code & 0x0f) as u8;
+        // Same as the decoder.
+        self.decode(code);
+        code
+    }
+}
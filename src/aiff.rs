@@ -0,0 +1,560 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::error::*;
+use crate::{DecodeOptions, EncodeOptions, SubChunk, WavFile, WaveFormat, WAVEFORMAT_ID_IEEE_FLOAT, WAVEFORMAT_ID_PCM};
+
+/// "FVER" chunk body of AIFF-C version 1. (May 23, 1990, 2:40pm)
+const AIFC_VERSION_1: [u8; 4] = [0xa2, 0x80, 0x51, 0x40];
+
+/// "COMM" chunk body size of AIFF.
+/// * 2 = channel
+/// * 4 = number of sample frames
+/// * 2 = sample size (bits)
+/// * 10 = sampling rate (80bit IEEE extended)
+const AIFF_COMM_CHUNK_SIZE: usize = 18;
+
+/// Container of aiff data.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AiffContainer {
+    /// "AIFF". Integer PCM is big endian. IEEE float is written as `Aifc` since "AIFF" can not have it.
+    Aiff,
+    /// "AIFC". Integer PCM is "NONE" (big endian). IEEE float is "fl32" or "fl64".
+    Aifc,
+    /// "AIFC". Integer PCM is "sowt" (little endian). IEEE float is "fl32" or "fl64".
+    AifcSowt,
+}
+
+/// Aiff file structure. Audio data is converted to and from the same `(WaveFormat, Vec<Vec<f64>>)` as `WavFile`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AiffFile {
+    /// Path of aiff file.
+    pub file_path: PathBuf,
+    /// Container of aiff file. Set it before updating audio data because "COMM" chunk depends on it.
+    pub container: AiffContainer,
+    /// Chunk vec. Chunk sizes are big endian in the file.
+    pub sub_chunks: Vec<SubChunk>,
+}
+
+impl Default for AiffFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiffFile {
+    /// Create structure.
+    pub fn new() -> AiffFile {
+        AiffFile {
+            file_path: PathBuf::new(),
+            container: AiffContainer::Aiff,
+            sub_chunks: Vec::new(),
+        }
+    }
+
+    /// Open aiff file and Create structure with the file data.
+    pub fn open(file_path: &Path) -> Result<AiffFile> {
+        // -- Check Parameter --
+        if !file_path.is_file() {
+            return Err(WavF64VecError::new(WavF64VecErrorKind::PathIsNotFile, None));
+        }
+
+        let mut aiff_file = Self::from_reader(BufReader::new(File::open(file_path)?))?;
+        aiff_file.file_path = file_path.to_path_buf();
+        Ok(aiff_file)
+    }
+
+    /// Create structure with the aiff data read from the reader until EOF.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<AiffFile> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Self::from_bytes(&buf)
+    }
+
+    /// Create structure with the aiff data in the bytes.
+    pub fn from_bytes(buf: &[u8]) -> Result<AiffFile> {
+        // 12 = "FORM" + FORM Size + "AIFF"
+        if buf.len() < 12 || buf[0x00..0x04] != [b'F', b'O', b'R', b'M'] {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::FileIsNotCompatibleFormat,
+                Some("\"FORM\"".to_string()),
+                None,
+                0,
+            ));
+        }
+        let form_size = u64::from(u32::from_be_bytes(<[u8; 4]>::try_from(&buf[0x04..0x08])?));
+        let is_aifc = match buf[0x08..0x0c] {
+            [b'A', b'I', b'F', b'F'] => false,
+            [b'A', b'I', b'F', b'C'] => true,
+            _ => {
+                return Err(WavF64VecError::new_at(
+                    WavF64VecErrorKind::FileIsNotCompatibleFormat,
+                    Some("\"AIFF\"".to_string()),
+                    None,
+                    0x08,
+                ));
+            }
+        };
+        if 8 + form_size > buf.len() as u64 {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("FORM size".to_string()),
+                None,
+                0x04,
+            ));
+        }
+
+        let chunks_end = usize::try_from(8 + form_size)?;
+        let mut sub_chunks: Vec<SubChunk> = Vec::new();
+        let mut chunk_head = 12;
+        // 8 = chunk_id + body_size
+        while chunk_head + 8 <= chunks_end {
+            let chunk_id = <[u8; 4]>::try_from(&buf[chunk_head..chunk_head + 4])?;
+            let body_size = usize::try_from(u32::from_be_bytes(<[u8; 4]>::try_from(&buf[chunk_head + 4..chunk_head + 8])?))?;
+            let body_head = chunk_head + 8;
+            if body_size > chunks_end - body_head {
+                return Err(WavF64VecError::new_at(
+                    WavF64VecErrorKind::SubChunkSizeError,
+                    None,
+                    Some(chunk_id),
+                    chunk_head as u64 + 4,
+                ));
+            }
+            sub_chunks.push(SubChunk {
+                chunk_id,
+                bytes_data_vec: buf[body_head..body_head + body_size].to_vec(),
+            });
+            // Odd-length body is followed by a pad byte.
+            chunk_head = body_head + body_size + body_size % 2;
+        }
+
+        let mut aiff_file = AiffFile {
+            file_path: PathBuf::new(),
+            container: AiffContainer::Aiff,
+            sub_chunks,
+        };
+        if is_aifc {
+            aiff_file.container = match aiff_file.get_compression_type()? {
+                Some([b's', b'o', b'w', b't']) => AiffContainer::AifcSowt,
+                _ => AiffContainer::Aifc,
+            };
+        }
+        Ok(aiff_file)
+    }
+
+    /// Save self to aiff file.
+    pub fn save(&mut self) -> Result<()> {
+        let file_path = self.file_path.clone();
+        self.save_as(&file_path)?;
+        Ok(())
+    }
+
+    /// Save self to aiff file as argument path.
+    pub fn save_as(&mut self, file_path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(file_path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+
+        // Update Self Infomation
+        self.file_path = file_path.to_path_buf();
+        Ok(())
+    }
+
+    /// Write self as aiff data to the writer.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        // -- Check Parameter --
+        let mut chunks_size: u64 = 0;
+        for sub_chunk in &self.sub_chunks {
+            // 8 = chunk_id + body_size, odd-length body is followed by a pad byte.
+            let body_size = sub_chunk.bytes_data_vec.len() as u64;
+            chunks_size += 8 + body_size + body_size % 2;
+        }
+        // 4 = "AIFF"
+        let form_size = 4 + chunks_size;
+        if form_size > u64::from(u32::MAX) {
+            return Err(WavF64VecError::new(WavF64VecErrorKind::SubChunkSizeTooLarge, None));
+        }
+        let is_aifc = self.container != AiffContainer::Aiff;
+        if self.find_sub_chunk([b'C', b'O', b'M', b'M']).is_some() && self.get_compression_type()?.is_some() != is_aifc {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("\"COMM\" is different from container".to_string()),
+            ));
+        }
+
+        writer.write_all(b"FORM")?;
+        writer.write_all(&(form_size as u32).to_be_bytes())?;
+        if is_aifc {
+            writer.write_all(b"AIFC")?;
+        } else {
+            writer.write_all(b"AIFF")?;
+        }
+        for sub_chunk in &self.sub_chunks {
+            writer.write_all(&sub_chunk.chunk_id)?;
+            writer.write_all(&(sub_chunk.bytes_data_vec.len() as u32).to_be_bytes())?;
+            writer.write_all(&sub_chunk.bytes_data_vec)?;
+            if sub_chunk.bytes_data_vec.len() % 2 == 1 {
+                writer.write_all(&[0x00])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get self as aiff data bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf: Vec<u8> = Vec::new();
+        self.write_to(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Get WaveFormat. If "COMM" chunk does not exist, return None.
+    /// Integer PCM which is not a multiple of 8 bits (e.g. 12bit) is left-justified, so it is read as the next multiple of 8 bits.
+    pub fn get_format(&self) -> Result<Option<WaveFormat>> {
+        match self.find_sub_chunk([b'C', b'O', b'M', b'M']) {
+            Some(comm_chunk) => Ok(Some(Self::read_comm_chunk(&comm_chunk.bytes_data_vec)?.0)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get audio data. Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
+    pub fn get_audio_for_channel_data_vec(&self) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
+        self.get_audio_for_channel_data_vec_with_options(&DecodeOptions::default())
+    }
+
+    /// Get audio data with the scaling of the options. Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
+    pub fn get_audio_for_channel_data_vec_with_options(&self, options: &DecodeOptions) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
+        let (wave_format, bytes_data_vec) = self.get_bytes_audio()?;
+        let channel_data_vec = WavFile::to_channel_data_vec(&wave_format, bytes_data_vec, options)?;
+        Ok((wave_format, channel_data_vec))
+    }
+
+    /// Get audio data. Return Value: `Vec<Vec<f64>>`: Outer is data vec. Inner is channel vec.
+    pub fn get_audio_for_data_channel_vec(&self) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
+        self.get_audio_for_data_channel_vec_with_options(&DecodeOptions::default())
+    }
+
+    /// Get audio data with the scaling of the options. Return Value: `Vec<Vec<f64>>`: Outer is data vec. Inner is channel vec.
+    pub fn get_audio_for_data_channel_vec_with_options(&self, options: &DecodeOptions) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
+        let (wave_format, bytes_data_vec) = self.get_bytes_audio()?;
+        let data_channel_vec = WavFile::to_data_channel_vec(&wave_format, bytes_data_vec, options)?;
+        Ok((wave_format, data_channel_vec))
+    }
+
+    /// Update audio data (update "COMM" and "SSND" chunk). If "COMM" or "SSND" chunk do not exist, those chunks are added.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is channel vec. Inner is data vec.
+    pub fn update_audio_for_channel_data_vec(
+        &mut self,
+        wave_format: &WaveFormat,
+        channel_data_vec: &Vec<Vec<f64>>,
+    ) -> Result<()> {
+        self.update_audio_for_channel_data_vec_with_options(wave_format, channel_data_vec, &EncodeOptions::default())
+    }
+
+    /// Update audio data with the dither and the noise shaping of the options.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is channel vec. Inner is data vec.
+    pub fn update_audio_for_channel_data_vec_with_options(
+        &mut self,
+        wave_format: &WaveFormat,
+        channel_data_vec: &Vec<Vec<f64>>,
+        options: &EncodeOptions,
+    ) -> Result<()> {
+        Self::aiff_format_check(wave_format)?;
+        let bytes_data_vec = WavFile::from_channel_data_vec_with_options(wave_format, channel_data_vec, options)?;
+        self.update_audio(wave_format, bytes_data_vec)
+    }
+
+    /// Update audio data (update "COMM" and "SSND" chunk). If "COMM" or "SSND" chunk do not exist, those chunks are added.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is data vec. Inner is channel vec.
+    pub fn update_audio_for_data_channel_vec(
+        &mut self,
+        wave_format: &WaveFormat,
+        data_channel_vec: &Vec<Vec<f64>>,
+    ) -> Result<()> {
+        self.update_audio_for_data_channel_vec_with_options(wave_format, data_channel_vec, &EncodeOptions::default())
+    }
+
+    /// Update audio data with the dither and the noise shaping of the options.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is data vec. Inner is channel vec.
+    pub fn update_audio_for_data_channel_vec_with_options(
+        &mut self,
+        wave_format: &WaveFormat,
+        data_channel_vec: &Vec<Vec<f64>>,
+        options: &EncodeOptions,
+    ) -> Result<()> {
+        Self::aiff_format_check(wave_format)?;
+        let bytes_data_vec = WavFile::from_data_channel_vec_with_options(wave_format, data_channel_vec, options)?;
+        self.update_audio(wave_format, bytes_data_vec)
+    }
+
+    /// Update a specified chunk. If the argument chunk's identifer does not exist, the argument chunk is added.
+    pub fn update_sub_chunk(&mut self, new_chunk: SubChunk) {
+        match self
+            .sub_chunks
+            .iter()
+            .position(|existing_chunk| existing_chunk.chunk_id == new_chunk.chunk_id)
+        {
+            Some(idx) => self.sub_chunks[idx] = new_chunk,
+            None => self.sub_chunks.push(new_chunk),
+        }
+    }
+
+    /// Delete a specified chunk. If the chunk was deleted, return true. If the argument chunk identifer does not exist, return false.
+    pub fn delete_sub_chunk(&mut self, sub_chunk_id: [u8; 4]) -> bool {
+        match self
+            .sub_chunks
+            .iter()
+            .position(|existing_chunk| existing_chunk.chunk_id == sub_chunk_id)
+        {
+            Some(idx) => {
+                self.sub_chunks.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn find_sub_chunk(&self, sub_chunk_id: [u8; 4]) -> Option<&SubChunk> {
+        self.sub_chunks.iter().find(|sub_chunk| sub_chunk.chunk_id == sub_chunk_id)
+    }
+
+    /// Get the compression type of "COMM" chunk. "AIFF" has no compression type.
+    fn get_compression_type(&self) -> Result<Option<[u8; 4]>> {
+        match self.find_sub_chunk([b'C', b'O', b'M', b'M']) {
+            Some(comm_chunk) => Ok(Self::read_comm_chunk(&comm_chunk.bytes_data_vec)?.2),
+            None => Ok(None),
+        }
+    }
+
+    /// Read "COMM" chunk. Return Value: (WaveFormat, number of sample frames, compression type)
+    fn read_comm_chunk(chunk_body: &[u8]) -> Result<(WaveFormat, usize, Option<[u8; 4]>)> {
+        if chunk_body.len() < AIFF_COMM_CHUNK_SIZE {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"COMM\"".to_string()),
+            ));
+        }
+        let channel = usize::from(u16::from_be_bytes(<[u8; 2]>::try_from(&chunk_body[0x00..0x02])?));
+        let frame_len = usize::try_from(u32::from_be_bytes(<[u8; 4]>::try_from(&chunk_body[0x02..0x06])?))?;
+        let sample_size = usize::from(u16::from_be_bytes(<[u8; 2]>::try_from(&chunk_body[0x06..0x08])?));
+        let sampling_rate = extended_to_f64(&<[u8; 10]>::try_from(&chunk_body[0x08..0x12])?).round();
+        if !(1.0..=u32::MAX as f64).contains(&sampling_rate) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("sampling rate".to_string()),
+            ));
+        }
+        // 4 = compression type
+        let op_compression_type = if chunk_body.len() >= AIFF_COMM_CHUNK_SIZE + 4 {
+            Some(<[u8; 4]>::try_from(&chunk_body[0x12..0x16])?)
+        } else {
+            None
+        };
+        let (id, bits) = match op_compression_type {
+            None | Some([b'N', b'O', b'N', b'E']) | Some([b't', b'w', b'o', b's']) | Some([b's', b'o', b'w', b't']) => {
+                (WAVEFORMAT_ID_PCM, sample_size.div_ceil(8) * 8)
+            }
+            Some([b'f', b'l', b'3', b'2']) | Some([b'F', b'L', b'3', b'2']) => (WAVEFORMAT_ID_IEEE_FLOAT, 32),
+            Some([b'f', b'l', b'6', b'4']) | Some([b'F', b'L', b'6', b'4']) => (WAVEFORMAT_ID_IEEE_FLOAT, 64),
+            Some(_) => {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::FormatIsNotSupported,
+                    Some("compression type".to_string()),
+                ));
+            }
+        };
+        let wave_format = WaveFormat {
+            id,
+            channel,
+            sampling_rate: sampling_rate as usize,
+            bits,
+        };
+        WaveFormat::format_check(&wave_format)?;
+        Ok((wave_format, frame_len, op_compression_type))
+    }
+
+    /// Make "COMM" chunk body for the container.
+    fn make_comm_chunk(&self, wave_format: &WaveFormat, frame_len: usize) -> Result<Vec<u8>> {
+        let mut chunk_body: Vec<u8> = Vec::new();
+        // channel
+        chunk_body.extend_from_slice(&u16::try_from(wave_format.channel)?.to_be_bytes());
+        // Number of Sample Frames
+        chunk_body.extend_from_slice(&u32::try_from(frame_len)?.to_be_bytes());
+        // Sample Size
+        chunk_body.extend_from_slice(&u16::try_from(wave_format.bits)?.to_be_bytes());
+        // Sampling Rate
+        chunk_body.extend_from_slice(&u64_to_extended(wave_format.sampling_rate as u64));
+        if self.container != AiffContainer::Aiff {
+            // Compression Type and Compression Name (Pascal string padded to even length)
+            let (compression_type, compression_name): (&[u8; 4], &[u8]) = match wave_format {
+                WaveFormat {
+                    id: WAVEFORMAT_ID_IEEE_FLOAT,
+                    bits: 32,
+                    ..
+                } => (b"fl32", b"32-bit floating point"),
+                WaveFormat {
+                    id: WAVEFORMAT_ID_IEEE_FLOAT,
+                    ..
+                } => (b"fl64", b"64-bit floating point"),
+                _ if self.container == AiffContainer::AifcSowt => (b"sowt", b"not compressed"),
+                _ => (b"NONE", b"not compressed"),
+            };
+            chunk_body.extend_from_slice(compression_type);
+            chunk_body.push(compression_name.len() as u8);
+            chunk_body.extend_from_slice(compression_name);
+            if compression_name.len() % 2 == 0 {
+                chunk_body.push(0x00);
+            }
+        }
+        Ok(chunk_body)
+    }
+
+    fn get_bytes_audio(&self) -> Result<(WaveFormat, Vec<u8>)> {
+        let comm_chunk = match self.find_sub_chunk([b'C', b'O', b'M', b'M']) {
+            Some(comm_chunk) => comm_chunk,
+            None => {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::NoRequiredSubChunk,
+                    Some("\"COMM\"".to_string()),
+                ));
+            }
+        };
+        let ssnd_chunk = match self.find_sub_chunk([b'S', b'S', b'N', b'D']) {
+            Some(ssnd_chunk) => ssnd_chunk,
+            None => {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::NoRequiredSubChunk,
+                    Some("\"SSND\"".to_string()),
+                ));
+            }
+        };
+        let (wave_format, frame_len, op_compression_type) = Self::read_comm_chunk(&comm_chunk.bytes_data_vec)?;
+
+        // 8 = offset + block size
+        let chunk_body = &ssnd_chunk.bytes_data_vec;
+        if chunk_body.len() < 8 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"SSND\"".to_string()),
+            ));
+        }
+        let data_head = 8 + usize::try_from(u32::from_be_bytes(<[u8; 4]>::try_from(&chunk_body[0x00..0x04])?))?;
+        let op_data_size = frame_len
+            .checked_mul(wave_format.channel)
+            .and_then(|sample_len| sample_len.checked_mul(wave_format.bits / 8));
+        let data_size = match op_data_size {
+            Some(data_size) => data_size,
+            None => {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::SubChunkSizeError,
+                    Some("\"SSND\"".to_string()),
+                ));
+            }
+        };
+        if data_head > chunk_body.len() || chunk_body.len() - data_head < data_size {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"SSND\"".to_string()),
+            ));
+        }
+        let mut bytes_data_vec = chunk_body[data_head..data_head + data_size].to_vec();
+        let is_big_endian = op_compression_type != Some([b's', b'o', b'w', b't']);
+        swap_sample_bytes(&wave_format, &mut bytes_data_vec, is_big_endian);
+        Ok((wave_format, bytes_data_vec))
+    }
+
+    fn update_audio(&mut self, wave_format: &WaveFormat, mut bytes_data_vec: Vec<u8>) -> Result<()> {
+        if self.container == AiffContainer::Aiff && wave_format.id == WAVEFORMAT_ID_IEEE_FLOAT {
+            self.container = AiffContainer::Aifc;
+        }
+        let frame_len = bytes_data_vec.len() / (wave_format.channel * wave_format.bits / 8);
+        let comm_chunk_body = self.make_comm_chunk(wave_format, frame_len)?;
+        swap_sample_bytes(wave_format, &mut bytes_data_vec, self.container != AiffContainer::AifcSowt);
+        // Offset and Block Size are 0.
+        let mut ssnd_chunk_body = vec![0u8; 8];
+        ssnd_chunk_body.append(&mut bytes_data_vec);
+
+        // "AIFC" requires "FVER" chunk before "COMM" chunk.
+        if self.container == AiffContainer::Aiff {
+            self.delete_sub_chunk([b'F', b'V', b'E', b'R']);
+        } else if self.find_sub_chunk([b'F', b'V', b'E', b'R']).is_none() {
+            self.sub_chunks.insert(
+                0,
+                SubChunk {
+                    chunk_id: [b'F', b'V', b'E', b'R'],
+                    bytes_data_vec: AIFC_VERSION_1.to_vec(),
+                },
+            );
+        }
+        self.update_sub_chunk(SubChunk {
+            chunk_id: [b'C', b'O', b'M', b'M'],
+            bytes_data_vec: comm_chunk_body,
+        });
+        self.update_sub_chunk(SubChunk {
+            chunk_id: [b'S', b'S', b'N', b'D'],
+            bytes_data_vec: ssnd_chunk_body,
+        });
+        Ok(())
+    }
+
+    fn aiff_format_check(wave_format: &WaveFormat) -> Result<()> {
+        WaveFormat::format_check(wave_format)?;
+        if wave_format.id != WAVEFORMAT_ID_PCM && wave_format.id != WAVEFORMAT_ID_IEEE_FLOAT {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("format id".to_string()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Convert between the sample bytes of aiff and wav. (The conversion is its own inverse.)
+/// Big endian samples are reversed. 8bit integer PCM is signed in aiff and unsigned in wav.
+fn swap_sample_bytes(wave_format: &WaveFormat, bytes_data_vec: &mut [u8], is_big_endian: bool) {
+    let size = wave_format.bits / 8;
+    if wave_format.id == WAVEFORMAT_ID_PCM && size == 1 {
+        for byte in bytes_data_vec.iter_mut() {
+            *byte ^= 0x80;
+        }
+    } else if is_big_endian {
+        for sample_bytes in bytes_data_vec.chunks_exact_mut(size) {
+            sample_bytes.reverse();
+        }
+    }
+}
+
+/// Convert from a 80bit IEEE extended value (big endian) to f64.
+fn extended_to_f64(bytes: &[u8; 10]) -> f64 {
+    let sign_exponent = u16::from_be_bytes([bytes[0], bytes[1]]);
+    let mut mantissa_bytes = [0u8; 8];
+    mantissa_bytes.copy_from_slice(&bytes[2..10]);
+    let mantissa = u64::from_be_bytes(mantissa_bytes);
+    let exponent = i32::from(sign_exponent & 0x7fff);
+    if exponent == 0 && mantissa == 0 {
+        return 0.0;
+    }
+    // 16383 = exponent bias, 63 = mantissa has the explicit integer bit
+    let val = mantissa as f64 * 2f64.powi(exponent - 16383 - 63);
+    if sign_exponent & 0x8000 != 0 {
+        -val
+    } else {
+        val
+    }
+}
+
+/// Convert from a integer value to the 80bit IEEE extended value (big endian).
+fn u64_to_extended(val: u64) -> [u8; 10] {
+    let mut bytes = [0u8; 10];
+    if val == 0 {
+        return bytes;
+    }
+    let shift = val.leading_zeros();
+    let exponent = (16383 + 63 - shift) as u16;
+    bytes[0..2].copy_from_slice(&exponent.to_be_bytes());
+    bytes[2..10].copy_from_slice(&(val << shift).to_be_bytes());
+    bytes
+}
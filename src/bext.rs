@@ -0,0 +1,228 @@
+use crate::error::*;
+use crate::{SubChunk, WavFile};
+
+/// "bext" chunk body size without the coding history.
+/// * 256 = description
+/// * 32 = originator
+/// * 32 = originator reference
+/// * 10 = origination date
+/// * 8 = origination time
+/// * 8 = time reference
+/// * 2 = version
+/// * 64 = UMID
+/// * 10 = loudness fields
+/// * 180 = reserved
+const BEXT_CHUNK_MIN_SIZE: usize = 602;
+
+/// Broadcast Wave Format (EBU Tech 3285) "bext" chunk.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BextChunk {
+    /// Description. (up to 256 bytes)
+    pub description: String,
+    /// Originator. (up to 32 bytes)
+    pub originator: String,
+    /// Originator reference. (up to 32 bytes)
+    pub originator_reference: String,
+    /// Origination date. ("yyyy-mm-dd")
+    pub origination_date: String,
+    /// Origination time. ("hh-mm-ss")
+    pub origination_time: String,
+    /// Samples since midnight of the first sample.
+    pub time_reference: u64,
+    /// Version. UMID is written from version 1, and the loudness fields are written from version 2.
+    pub version: u16,
+    /// SMPTE UMID. (Version 1 or later)
+    pub umid: [u8; 64],
+    /// Integrated loudness in 0.01 LUFS. (Version 2 or later)
+    pub loudness_value: i16,
+    /// Loudness range in 0.01 LU. (Version 2 or later)
+    pub loudness_range: i16,
+    /// Maximum true peak level in 0.01 dBTP. (Version 2 or later)
+    pub max_true_peak_level: i16,
+    /// Maximum momentary loudness in 0.01 LUFS. (Version 2 or later)
+    pub max_momentary_loudness: i16,
+    /// Maximum short term loudness in 0.01 LUFS. (Version 2 or later)
+    pub max_short_term_loudness: i16,
+    /// Coding history. Lines are terminated by CR/LF.
+    pub coding_history: String,
+}
+
+impl Default for BextChunk {
+    fn default() -> Self {
+        BextChunk {
+            description: String::new(),
+            originator: String::new(),
+            originator_reference: String::new(),
+            origination_date: String::new(),
+            origination_time: String::new(),
+            time_reference: 0,
+            version: 2,
+            umid: [0x00; 64],
+            loudness_value: 0,
+            loudness_range: 0,
+            max_true_peak_level: 0,
+            max_momentary_loudness: 0,
+            max_short_term_loudness: 0,
+            coding_history: String::new(),
+        }
+    }
+}
+
+impl BextChunk {
+    /// Create structure. (Version 2)
+    pub fn new() -> BextChunk {
+        Self::default()
+    }
+
+    /// Create structure with the "bext" chunk.
+    /// UMID of version 0 and the loudness fields of version 0 and 1 are reserved, so they are read as 0.
+    pub fn from_sub_chunk(sub_chunk: &SubChunk) -> Result<BextChunk> {
+        let chunk_body = &sub_chunk.bytes_data_vec;
+        if sub_chunk.chunk_id != [b'b', b'e', b'x', b't'] || chunk_body.len() < BEXT_CHUNK_MIN_SIZE {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"bext\"".to_string()),
+            ));
+        }
+        let time_reference_low = u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[0x152..0x156])?);
+        let time_reference_high = u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[0x156..0x15a])?);
+        let version = u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x15a..0x15c])?);
+        let mut bext_chunk = BextChunk {
+            description: Self::decode_text(&chunk_body[0x000..0x100]),
+            originator: Self::decode_text(&chunk_body[0x100..0x120]),
+            originator_reference: Self::decode_text(&chunk_body[0x120..0x140]),
+            origination_date: Self::decode_text(&chunk_body[0x140..0x14a]),
+            origination_time: Self::decode_text(&chunk_body[0x14a..0x152]),
+            time_reference: (u64::from(time_reference_high) << 32) | u64::from(time_reference_low),
+            version,
+            coding_history: Self::decode_text(&chunk_body[BEXT_CHUNK_MIN_SIZE..]),
+            ..Default::default()
+        };
+        if version >= 1 {
+            bext_chunk.umid.copy_from_slice(&chunk_body[0x15c..0x19c]);
+        }
+        if version >= 2 {
+            let loudness_vec: Vec<i16> = chunk_body[0x19c..0x1a6]
+                .chunks_exact(2)
+                .map(|value_bytes| i16::from_le_bytes([value_bytes[0], value_bytes[1]]))
+                .collect();
+            bext_chunk.loudness_value = loudness_vec[0];
+            bext_chunk.loudness_range = loudness_vec[1];
+            bext_chunk.max_true_peak_level = loudness_vec[2];
+            bext_chunk.max_momentary_loudness = loudness_vec[3];
+            bext_chunk.max_short_term_loudness = loudness_vec[4];
+        }
+        Ok(bext_chunk)
+    }
+
+    /// Make "bext" chunk. Text longer than the field is an error.
+    pub fn to_sub_chunk(&self) -> Result<SubChunk> {
+        let mut chunk_body: Vec<u8> = Vec::new();
+        Self::append_text(&mut chunk_body, &self.description, 256, "description")?;
+        Self::append_text(&mut chunk_body, &self.originator, 32, "originator")?;
+        Self::append_text(&mut chunk_body, &self.originator_reference, 32, "originator reference")?;
+        Self::append_text(&mut chunk_body, &self.origination_date, 10, "origination date")?;
+        Self::append_text(&mut chunk_body, &self.origination_time, 8, "origination time")?;
+        // Time Reference (low, high)
+        chunk_body.extend_from_slice(&self.time_reference.to_le_bytes());
+        chunk_body.extend_from_slice(&self.version.to_le_bytes());
+        if self.version >= 1 {
+            chunk_body.extend_from_slice(&self.umid);
+        } else {
+            chunk_body.extend_from_slice(&[0x00; 64]);
+        }
+        for loudness in [
+            self.loudness_value,
+            self.loudness_range,
+            self.max_true_peak_level,
+            self.max_momentary_loudness,
+            self.max_short_term_loudness,
+        ] {
+            if self.version >= 2 {
+                chunk_body.extend_from_slice(&loudness.to_le_bytes());
+            } else {
+                chunk_body.extend_from_slice(&[0x00; 2]);
+            }
+        }
+        // Reserved
+        chunk_body.extend_from_slice(&[0x00; 180]);
+        chunk_body.extend_from_slice(self.coding_history.as_bytes());
+        Ok(SubChunk {
+            chunk_id: [b'b', b'e', b'x', b't'],
+            bytes_data_vec: chunk_body,
+        })
+    }
+
+    /// Get the timestamp of the first sample from the time reference.
+    /// Return Value: (u64, u64): Seconds since midnight, and the samples within the second.
+    pub fn get_timestamp(&self, sampling_rate: usize) -> Result<(u64, u64)> {
+        // -- Check Parameter --
+        if sampling_rate == 0 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::ParameterIsOutOfRange,
+                Some("sampling rate".to_string()),
+            ));
+        }
+        let sampling_rate = sampling_rate as u64;
+        Ok((self.time_reference / sampling_rate, self.time_reference % sampling_rate))
+    }
+
+    /// Set the time reference from the timestamp. (Seconds since midnight, and the samples within the second)
+    pub fn set_timestamp(&mut self, seconds: u64, samples: u64, sampling_rate: usize) {
+        self.time_reference = seconds * sampling_rate as u64 + samples;
+    }
+
+    /// Decode the text up to the first null.
+    fn decode_text(bytes: &[u8]) -> String {
+        let text_len = bytes.iter().position(|byte| *byte == 0x00).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..text_len]).into_owned()
+    }
+
+    /// Append the text padded with nulls to the field size.
+    fn append_text(chunk_body: &mut Vec<u8>, text: &str, field_size: usize, field_name: &str) -> Result<()> {
+        if text.len() > field_size {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::ParameterIsOutOfRange,
+                Some(field_name.to_string()),
+            ));
+        }
+        chunk_body.extend_from_slice(text.as_bytes());
+        chunk_body.append(&mut vec![0x00; field_size - text.len()]);
+        Ok(())
+    }
+}
+
+impl WavFile {
+    /// Get "bext" chunk. If it does not exist, return None.
+    pub fn get_bext(&self) -> Result<Option<BextChunk>> {
+        match self
+            .sub_chunks
+            .iter()
+            .find(|sub_chunk| sub_chunk.chunk_id == [b'b', b'e', b'x', b't'])
+        {
+            Some(sub_chunk) => Ok(Some(BextChunk::from_sub_chunk(sub_chunk)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Update "bext" chunk. If it does not exist, the chunk is added.
+    pub fn update_bext(&mut self, bext_chunk: &BextChunk) -> Result<()> {
+        self.update_sub_chunk(bext_chunk.to_sub_chunk()?)
+    }
+
+    /// Get the timestamp of the first sample from the time reference of "bext" chunk and the sampling rate of "fmt" chunk.
+    /// Return Value: (u64, u64): Seconds since midnight, and the samples within the second. If "bext" chunk does not exist, return None.
+    pub fn get_bext_timestamp(&self) -> Result<Option<(u64, u64)>> {
+        let bext_chunk = match self.get_bext()? {
+            Some(bext_chunk) => bext_chunk,
+            None => return Ok(None),
+        };
+        match self.get_format()? {
+            Some(wave_format) => Ok(Some(bext_chunk.get_timestamp(wave_format.sampling_rate)?)),
+            None => Err(WavF64VecError::new(
+                WavF64VecErrorKind::NoRequiredSubChunk,
+                Some("\"fmt\"".to_string()),
+            )),
+        }
+    }
+}
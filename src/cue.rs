@@ -0,0 +1,345 @@
+use crate::error::*;
+use crate::{SubChunk, WavFile};
+
+/// Size of a cue point in "cue " chunk.
+/// * 4 = id
+/// * 4 = position
+/// * 4 = chunk id ("data")
+/// * 4 = chunk start
+/// * 4 = block start
+/// * 4 = sample offset
+const CUE_POINT_SIZE: usize = 24;
+
+/// "ltxt" entry body size without the text.
+/// * 4 = cue point id
+/// * 4 = sample length
+/// * 4 = purpose id
+/// * 8 = country, language, dialect and code page
+const LTXT_ENTRY_MIN_SIZE: usize = 20;
+
+/// Cue point of "cue " chunk with its "labl", "note" and "ltxt" entries of "LIST" "adtl" chunk.
+/// A cue point which has a length is a region.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CuePoint {
+    /// Identifier. Unique in the file.
+    pub id: u32,
+    /// Sample offset (frames) in "data" chunk.
+    pub position: u32,
+    /// Length of the region in samples. 0 is a point. ("ltxt")
+    pub length: u32,
+    /// Name. ("labl")
+    pub label: String,
+    /// Note. ("note")
+    pub note: String,
+}
+
+/// Bodies of "cue " chunk and "LIST" "adtl" chunk. None deletes the chunk.
+pub(crate) struct CueChunkBodies {
+    op_cue_chunk_body: Option<Vec<u8>>,
+    op_adtl_chunk_body: Option<Vec<u8>>,
+}
+
+impl CuePoint {
+    /// Create a point.
+    pub fn new_point(id: u32, position: u32, label: &str) -> CuePoint {
+        CuePoint {
+            id,
+            position,
+            label: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Create a region.
+    pub fn new_region(id: u32, position: u32, length: u32, label: &str) -> CuePoint {
+        CuePoint {
+            id,
+            position,
+            length,
+            label: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the cue point is a region.
+    pub fn is_region(&self) -> bool {
+        self.length > 0
+    }
+}
+
+impl WavFile {
+    /// Get the cue points ("cue " chunk) with the labels, notes and region lengths ("LIST" "adtl" chunk).
+    /// If "cue " chunk does not exist, return an empty vector.
+    pub fn get_cue_points(&self) -> Result<Vec<CuePoint>> {
+        let cue_chunk = match self
+            .sub_chunks
+            .iter()
+            .find(|sub_chunk| sub_chunk.chunk_id == [b'c', b'u', b'e', b' '])
+        {
+            Some(cue_chunk) => cue_chunk,
+            None => return Ok(Vec::new()),
+        };
+        let mut cue_point_vec = Self::read_cue_chunk(&cue_chunk.bytes_data_vec)?;
+        if let Some(chunk_idx) = self.get_list_chunk_idx([b'a', b'd', b't', b'l']) {
+            for entry in Self::read_adtl_entries(&self.sub_chunks[chunk_idx].bytes_data_vec)? {
+                // 4 = cue point id
+                if entry.bytes_data_vec.len() < 4 {
+                    return Err(WavF64VecError::new(
+                        WavF64VecErrorKind::SubChunkSizeError,
+                        Some(format!("\"{}\"", String::from_utf8_lossy(&entry.chunk_id))),
+                    ));
+                }
+                let id = u32::from_le_bytes(<[u8; 4]>::try_from(&entry.bytes_data_vec[0..4])?);
+                let cue_point = match cue_point_vec.iter_mut().find(|cue_point| cue_point.id == id) {
+                    Some(cue_point) => cue_point,
+                    None => continue,
+                };
+                match entry.chunk_id {
+                    [b'l', b'a', b'b', b'l'] => cue_point.label = Self::decode_cue_text(&entry.bytes_data_vec[4..]),
+                    [b'n', b'o', b't', b'e'] => cue_point.note = Self::decode_cue_text(&entry.bytes_data_vec[4..]),
+                    [b'l', b't', b'x', b't'] => {
+                        if entry.bytes_data_vec.len() < LTXT_ENTRY_MIN_SIZE {
+                            return Err(WavF64VecError::new(
+                                WavF64VecErrorKind::SubChunkSizeError,
+                                Some("\"ltxt\"".to_string()),
+                            ));
+                        }
+                        cue_point.length = u32::from_le_bytes(<[u8; 4]>::try_from(&entry.bytes_data_vec[4..8])?);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(cue_point_vec)
+    }
+
+    /// Update "cue " chunk and "LIST" "adtl" chunk. If they do not exist, the chunks are added.
+    /// "adtl" entries other than "labl", "note" and "ltxt" are kept. If the cue points are empty, "cue " chunk is deleted.
+    /// "ltxt" entries of the existing cue points keep the purpose, the country, language, dialect, code page and text.
+    pub fn update_cue_points(&mut self, cue_point_vec: &[CuePoint]) -> Result<()> {
+        let cue_chunk_bodies = self.make_cue_chunk_bodies(cue_point_vec)?;
+        self.set_cue_chunk_bodies(cue_chunk_bodies)
+    }
+
+    /// Make "cue " chunk and "LIST" "adtl" chunk of the cue points kept within the audio data of the frame length.
+    /// Cue points after the end of audio data are deleted, and regions over the end are shortened.
+    /// Return None if no cue point is changed, or the existing chunks can not be read. (The chunks are kept as they are.)
+    pub(crate) fn make_fitted_cue_chunk_bodies(&self, frame_len: usize) -> Option<CueChunkBodies> {
+        let cue_point_vec = self.get_cue_points().ok()?;
+        let frame_len = u32::try_from(frame_len).unwrap_or(u32::MAX);
+        let mut fitted_cue_point_vec: Vec<CuePoint> = cue_point_vec
+            .iter()
+            .filter(|cue_point| cue_point.position <= frame_len)
+            .cloned()
+            .collect();
+        for cue_point in &mut fitted_cue_point_vec {
+            cue_point.length = cue_point.length.min(frame_len - cue_point.position);
+        }
+        if fitted_cue_point_vec == cue_point_vec {
+            return None;
+        }
+        self.make_cue_chunk_bodies(&fitted_cue_point_vec).ok()
+    }
+
+    /// Replace "cue " chunk and "LIST" "adtl" chunk.
+    pub(crate) fn set_cue_chunk_bodies(&mut self, cue_chunk_bodies: CueChunkBodies) -> Result<()> {
+        match cue_chunk_bodies.op_cue_chunk_body {
+            Some(chunk_body) => self.update_sub_chunk(SubChunk {
+                chunk_id: [b'c', b'u', b'e', b' '],
+                bytes_data_vec: chunk_body,
+            })?,
+            None => {
+                self.delete_sub_chunk([b'c', b'u', b'e', b' ']);
+            }
+        }
+        match cue_chunk_bodies.op_adtl_chunk_body {
+            Some(chunk_body) => self.update_list_chunk(chunk_body)?,
+            None => {
+                if let Some(chunk_idx) = self.get_list_chunk_idx([b'a', b'd', b't', b'l']) {
+                    self.sub_chunks.remove(chunk_idx);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn make_cue_chunk_bodies(&self, cue_point_vec: &[CuePoint]) -> Result<CueChunkBodies> {
+        // -- Check Parameter --
+        for (idx, cue_point) in cue_point_vec.iter().enumerate() {
+            if cue_point_vec[..idx]
+                .iter()
+                .any(|other_cue_point| other_cue_point.id == cue_point.id)
+            {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::ParameterIsOutOfRange,
+                    Some("cue point id is duplicated".to_string()),
+                ));
+            }
+        }
+
+        let mut entry_vec: Vec<SubChunk> = Vec::new();
+        if let Some(chunk_idx) = self.get_list_chunk_idx([b'a', b'd', b't', b'l']) {
+            entry_vec = Self::read_adtl_entries(&self.sub_chunks[chunk_idx].bytes_data_vec)?;
+            // "ltxt" entries of the cue points keep the purpose, the locale and the text, and only the length is changed.
+            entry_vec.retain_mut(|entry| match entry.chunk_id {
+                [b'l', b'a', b'b', b'l'] | [b'n', b'o', b't', b'e'] => false,
+                [b'l', b't', b'x', b't'] => {
+                    if entry.bytes_data_vec.len() < LTXT_ENTRY_MIN_SIZE {
+                        return false;
+                    }
+                    let id = u32::from_le_bytes([
+                        entry.bytes_data_vec[0],
+                        entry.bytes_data_vec[1],
+                        entry.bytes_data_vec[2],
+                        entry.bytes_data_vec[3],
+                    ]);
+                    match cue_point_vec.iter().find(|cue_point| cue_point.id == id) {
+                        Some(cue_point) => {
+                            entry.bytes_data_vec[4..8].copy_from_slice(&cue_point.length.to_le_bytes());
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                _ => true,
+            });
+        }
+        let ltxt_id_vec: Vec<u32> = entry_vec
+            .iter()
+            .filter(|entry| entry.chunk_id == [b'l', b't', b'x', b't'])
+            .map(|entry| {
+                u32::from_le_bytes([
+                    entry.bytes_data_vec[0],
+                    entry.bytes_data_vec[1],
+                    entry.bytes_data_vec[2],
+                    entry.bytes_data_vec[3],
+                ])
+            })
+            .collect();
+        for cue_point in cue_point_vec {
+            if cue_point.is_region() && !ltxt_id_vec.contains(&cue_point.id) {
+                let mut entry_body: Vec<u8> = Vec::new();
+                entry_body.extend_from_slice(&cue_point.id.to_le_bytes());
+                entry_body.extend_from_slice(&cue_point.length.to_le_bytes());
+                // Purpose, and country, language, dialect and code page are not specified.
+                entry_body.extend_from_slice(b"rgn ");
+                entry_body.extend_from_slice(&[0x00; 8]);
+                entry_vec.push(SubChunk {
+                    chunk_id: [b'l', b't', b'x', b't'],
+                    bytes_data_vec: entry_body,
+                });
+            }
+            for (chunk_id, text) in [
+                ([b'l', b'a', b'b', b'l'], &cue_point.label),
+                ([b'n', b'o', b't', b'e'], &cue_point.note),
+            ] {
+                if !text.is_empty() {
+                    let mut entry_body: Vec<u8> = cue_point.id.to_le_bytes().to_vec();
+                    entry_body.extend_from_slice(text.as_bytes());
+                    // Terminating null
+                    entry_body.push(0x00);
+                    entry_vec.push(SubChunk {
+                        chunk_id,
+                        bytes_data_vec: entry_body,
+                    });
+                }
+            }
+        }
+
+        let mut cue_chunk_bodies = CueChunkBodies {
+            op_cue_chunk_body: None,
+            op_adtl_chunk_body: None,
+        };
+        if !cue_point_vec.is_empty() {
+            cue_chunk_bodies.op_cue_chunk_body = Some(Self::make_cue_chunk(cue_point_vec));
+        }
+        if !entry_vec.is_empty() {
+            let mut chunk_body: Vec<u8> = b"adtl".to_vec();
+            for entry in &entry_vec {
+                chunk_body.extend_from_slice(&entry.chunk_id);
+                chunk_body.extend_from_slice(&(entry.bytes_data_vec.len() as u32).to_le_bytes());
+                chunk_body.extend_from_slice(&entry.bytes_data_vec);
+                // Odd-length entry is followed by a pad byte.
+                if entry.bytes_data_vec.len() % 2 == 1 {
+                    chunk_body.push(0x00);
+                }
+            }
+            cue_chunk_bodies.op_adtl_chunk_body = Some(chunk_body);
+        }
+        Ok(cue_chunk_bodies)
+    }
+
+    fn read_cue_chunk(chunk_body: &[u8]) -> Result<Vec<CuePoint>> {
+        // 4 = number of cue points
+        if chunk_body.len() < 4 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"cue \"".to_string()),
+            ));
+        }
+        let cue_point_len = usize::try_from(u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[0..4])?))?;
+        if (chunk_body.len() - 4) / CUE_POINT_SIZE < cue_point_len {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"cue \"".to_string()),
+            ));
+        }
+        let mut cue_point_vec: Vec<CuePoint> = Vec::new();
+        for cue_point_bytes in chunk_body[4..4 + cue_point_len * CUE_POINT_SIZE].chunks_exact(CUE_POINT_SIZE) {
+            cue_point_vec.push(CuePoint {
+                id: u32::from_le_bytes(<[u8; 4]>::try_from(&cue_point_bytes[0x00..0x04])?),
+                // Sample Offset
+                position: u32::from_le_bytes(<[u8; 4]>::try_from(&cue_point_bytes[0x14..0x18])?),
+                ..Default::default()
+            });
+        }
+        Ok(cue_point_vec)
+    }
+
+    fn make_cue_chunk(cue_point_vec: &[CuePoint]) -> Vec<u8> {
+        let mut chunk_body: Vec<u8> = (cue_point_vec.len() as u32).to_le_bytes().to_vec();
+        for cue_point in cue_point_vec {
+            chunk_body.extend_from_slice(&cue_point.id.to_le_bytes());
+            // Position (play order)
+            chunk_body.extend_from_slice(&cue_point.position.to_le_bytes());
+            chunk_body.extend_from_slice(b"data");
+            // Chunk Start and Block Start are 0 for uncompressed "data" chunk.
+            chunk_body.extend_from_slice(&[0x00; 8]);
+            // Sample Offset
+            chunk_body.extend_from_slice(&cue_point.position.to_le_bytes());
+        }
+        chunk_body
+    }
+
+    /// Read the entries of "LIST" "adtl" chunk.
+    fn read_adtl_entries(chunk_body: &[u8]) -> Result<Vec<SubChunk>> {
+        let mut entry_vec: Vec<SubChunk> = Vec::new();
+        // 4 = "adtl"
+        let mut pos = 4;
+        // 8 = chunk_id + body_size
+        while chunk_body.len() - pos >= 8 {
+            let chunk_id = <[u8; 4]>::try_from(&chunk_body[pos..pos + 4])?;
+            let body_size = usize::try_from(u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[pos + 4..pos + 8])?))?;
+            let body_head = pos + 8;
+            if chunk_body.len() - body_head < body_size {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::SubChunkSizeError,
+                    Some(format!("\"{}\"", String::from_utf8_lossy(&chunk_id))),
+                ));
+            }
+            entry_vec.push(SubChunk {
+                chunk_id,
+                bytes_data_vec: chunk_body[body_head..body_head + body_size].to_vec(),
+            });
+            // Odd-length body is followed by a pad byte.
+            pos = (body_head + body_size + body_size % 2).min(chunk_body.len());
+        }
+        Ok(entry_vec)
+    }
+
+    /// Decode the text up to the first null.
+    fn decode_cue_text(bytes: &[u8]) -> String {
+        let text_len = bytes.iter().position(|byte| *byte == 0x00).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..text_len]).into_owned()
+    }
+}
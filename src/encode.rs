@@ -0,0 +1,145 @@
+use crate::error::*;
+use crate::scaling::*;
+use crate::{f64wave_to_bytes_with_scaling, WAVEFORMAT_ID_PCM};
+
+/// Dither added before quantizing to integer PCM.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Dither {
+    /// No dither. (Round to nearest)
+    None,
+    /// Rectangular PDF dither of ±0.5 LSB.
+    Rectangular,
+    /// Triangular PDF dither of ±1 LSB.
+    Triangular,
+}
+
+/// Noise shaping filter which feeds back the quantization error.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NoiseShaping {
+    /// No noise shaping.
+    None,
+    /// First order highpass. (1 - z^-1)
+    FirstOrder,
+    /// Second order highpass. (1 - z^-1)^2
+    SecondOrder,
+    /// 5 tap psychoacoustic filter by Lipshitz et al. for 44.1kHz.
+    Lipshitz,
+}
+
+impl NoiseShaping {
+    fn coefficients(&self) -> &'static [f64] {
+        match self {
+            NoiseShaping::None => &[],
+            NoiseShaping::FirstOrder => &[1.0],
+            NoiseShaping::SecondOrder => &[2.0, -1.0],
+            NoiseShaping::Lipshitz => &[2.033, -2.165, 1.959, -1.590, 0.6149],
+        }
+    }
+}
+
+/// Options of encoding audio data. The dither, the noise shaping and the scaling affect only integer PCM
+/// (including the 16bit PCM which is encoded to ADPCM). IEEE float and A-law/mu-law formats are not affected.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EncodeOptions {
+    /// Dither.
+    pub dither: Dither,
+    /// Noise shaping filter.
+    pub noise_shaping: NoiseShaping,
+    /// Seed of the dither. The same seed gives the same output.
+    pub seed: u64,
+    /// Scaling of integer PCM.
+    pub scaling: PcmScaling,
+    /// Block align (bytes per block) of ADPCM formats.
+    /// If None, it is 256 bytes per channel for each 11025Hz of the sampling rate.
+    pub op_block_align: Option<usize>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            dither: Dither::None,
+            noise_shaping: NoiseShaping::None,
+            seed: 0,
+            scaling: PcmScaling::default(),
+            op_block_align: None,
+        }
+    }
+}
+
+/// Options of decoding integer PCM to audio data. IEEE float formats are not affected.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct DecodeOptions {
+    /// Scaling of integer PCM.
+    pub scaling: PcmScaling,
+}
+
+/// Quantizer with the dither and the noise shaping state of each channel.
+pub(crate) struct Quantizer {
+    options: EncodeOptions,
+    rng_state: u64,
+    /// Past quantization errors of each channel (LSB). The latest is first.
+    error_history_vec: Vec<Vec<f64>>,
+}
+
+impl Quantizer {
+    pub(crate) fn new(options: &EncodeOptions, channel: usize) -> Quantizer {
+        Quantizer {
+            options: *options,
+            // xorshift state must not be 0.
+            rng_state: match options.seed ^ 0x9e3779b97f4a7c15 {
+                0 => 0x9e3779b97f4a7c15,
+                rng_state => rng_state,
+            },
+            error_history_vec: vec![vec![0.0; options.noise_shaping.coefficients().len()]; channel],
+        }
+    }
+
+    /// Convert from a audio data value(`f64`) of the channel to a bytes data vector.
+    pub(crate) fn f64wave_to_bytes(
+        &mut self,
+        format_id: usize,
+        f64_val: f64,
+        bits: usize,
+        channel_idx: usize,
+    ) -> Result<Vec<u8>> {
+        if format_id != WAVEFORMAT_ID_PCM
+            || (self.options.dither == Dither::None && self.options.noise_shaping == NoiseShaping::None)
+        {
+            return f64wave_to_bytes_with_scaling(format_id, f64_val, bits, &self.options.scaling);
+        }
+        let (min_val, max_val) = pcm_range(bits / 8)?;
+
+        // In LSB
+        let error_history = &mut self.error_history_vec[channel_idx];
+        let shaped_val = f64_val * self.options.scaling.full_scale(bits)?
+            - self
+                .options
+                .noise_shaping
+                .coefficients()
+                .iter()
+                .zip(error_history.iter())
+                .map(|(coefficient, error)| coefficient * error)
+                .sum::<f64>();
+        let dither_val = match self.options.dither {
+            Dither::None => 0.0,
+            Dither::Rectangular => next_uniform(&mut self.rng_state),
+            Dither::Triangular => next_uniform(&mut self.rng_state) + next_uniform(&mut self.rng_state),
+        };
+        let quantized_val = (shaped_val + dither_val).round().clamp(min_val, max_val);
+        if !error_history.is_empty() {
+            error_history.rotate_right(1);
+            // Limit the feedback when clipped.
+            error_history[0] = (quantized_val - shaped_val).clamp(-2.0, 2.0);
+        }
+        pcm_to_bytes(quantized_val, bits / 8)
+    }
+}
+
+/// Uniform random value in [-0.5, 0.5) by xorshift64*.
+fn next_uniform(rng_state: &mut u64) -> f64 {
+    *rng_state ^= *rng_state >> 12;
+    *rng_state ^= *rng_state << 25;
+    *rng_state ^= *rng_state >> 27;
+    let random_val = rng_state.wrapping_mul(0x2545f4914f6cdd1d);
+    (random_val >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+}
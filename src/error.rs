@@ -1,43 +1,145 @@
 use std::fmt;
 
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+/// Result of this crate.
+pub type Result<T> = std::result::Result<T, WavF64VecError>;
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct WavF64VecError {
-    pub err_kind: WavF64VecErrorKind,
-    pub op_additional_message: Option<String>,
+/// Error of this crate. The underlying error is available from `source()`.
+#[derive(Debug)]
+pub enum WavF64VecError {
+    /// I/O error.
+    Io(std::io::Error),
+    /// Integer conversion error.
+    IntConversion(std::num::TryFromIntError),
+    /// Slice conversion error.
+    SliceConversion(std::array::TryFromSliceError),
+    /// UTF-8 conversion error.
+    Utf8(std::string::FromUtf8Error),
+    /// Wav format error. `op_chunk_id` and `op_offset` are the sub chunk and the stream position where the error was found.
+    Format {
+        err_kind: WavF64VecErrorKind,
+        op_additional_message: Option<String>,
+        op_chunk_id: Option<[u8; 4]>,
+        op_offset: Option<u64>,
+    },
 }
 
 impl WavF64VecError {
-    pub fn new(
+    pub fn new(err_kind: WavF64VecErrorKind, op_additional_message: Option<String>) -> WavF64VecError {
+        WavF64VecError::Format {
+            err_kind,
+            op_additional_message,
+            op_chunk_id: None,
+            op_offset: None,
+        }
+    }
+
+    pub fn new_at(
         err_kind: WavF64VecErrorKind,
         op_additional_message: Option<String>,
-    ) -> Box<dyn std::error::Error + Send + Sync + 'static> {
-        Box::<WavF64VecError>::new(WavF64VecError {
-            err_kind: err_kind,
-            op_additional_message: op_additional_message,
-        })
+        op_chunk_id: Option<[u8; 4]>,
+        offset: u64,
+    ) -> WavF64VecError {
+        WavF64VecError::Format {
+            err_kind,
+            op_additional_message,
+            op_chunk_id,
+            op_offset: Some(offset),
+        }
+    }
+
+    /// Set the sub chunk and the stream position where the error was found, unless they are already set.
+    pub(crate) fn at(mut self, chunk_id: [u8; 4], op_position: Option<u64>) -> WavF64VecError {
+        if let WavF64VecError::Format {
+            op_chunk_id, op_offset, ..
+        } = &mut self
+        {
+            op_chunk_id.get_or_insert(chunk_id);
+            if op_offset.is_none() {
+                *op_offset = op_position;
+            }
+        }
+        self
+    }
+
+    /// Get WavF64VecErrorKind if the error is a wav format error.
+    pub fn err_kind(&self) -> Option<&WavF64VecErrorKind> {
+        match self {
+            WavF64VecError::Format { err_kind, .. } => Some(err_kind),
+            _ => None,
+        }
     }
 }
 
 impl fmt::Display for WavF64VecError {
     fn fmt(&self, f: &mut fmt::Formatter) -> std::result::Result<(), fmt::Error> {
-        for err_message in WAVE_AUDIO_ERR_MESSAGE {
-            if err_message.err_kind == self.err_kind {
-                if let Some(additional_message) = &self.op_additional_message {
-                    return write!(f, "{}", format!("{} : {}", err_message.message, additional_message));
-                } else {
-                    return write!(f, "{}", format!("{}", err_message.message));
+        match self {
+            WavF64VecError::Io(err) => write!(f, "I/O error : {}", err),
+            WavF64VecError::IntConversion(err) => write!(f, "Integer conversion error : {}", err),
+            WavF64VecError::SliceConversion(err) => write!(f, "Slice conversion error : {}", err),
+            WavF64VecError::Utf8(err) => write!(f, "UTF-8 conversion error : {}", err),
+            WavF64VecError::Format {
+                err_kind,
+                op_additional_message,
+                op_chunk_id,
+                op_offset,
+            } => {
+                for err_message in WAVE_AUDIO_ERR_MESSAGE {
+                    if err_message.err_kind == *err_kind {
+                        write!(f, "{}", err_message.message)?;
+                    }
+                }
+                if let Some(additional_message) = op_additional_message {
+                    write!(f, " : {}", additional_message)?;
+                }
+                if let Some(chunk_id) = op_chunk_id {
+                    write!(f, " (\"{}\" chunk)", String::from_utf8_lossy(chunk_id))?;
+                }
+                if let Some(offset) = op_offset {
+                    write!(f, " (offset {:#x})", offset)?;
                 }
+                Ok(())
             }
         }
-        panic!()
     }
 }
 
-impl std::error::Error for WavF64VecError {}
+impl std::error::Error for WavF64VecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WavF64VecError::Io(err) => Some(err),
+            WavF64VecError::IntConversion(err) => Some(err),
+            WavF64VecError::SliceConversion(err) => Some(err),
+            WavF64VecError::Utf8(err) => Some(err),
+            WavF64VecError::Format { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for WavF64VecError {
+    fn from(err: std::io::Error) -> WavF64VecError {
+        WavF64VecError::Io(err)
+    }
+}
+
+impl From<std::num::TryFromIntError> for WavF64VecError {
+    fn from(err: std::num::TryFromIntError) -> WavF64VecError {
+        WavF64VecError::IntConversion(err)
+    }
+}
 
-#[derive(Debug, Clone, PartialEq)]
+impl From<std::array::TryFromSliceError> for WavF64VecError {
+    fn from(err: std::array::TryFromSliceError) -> WavF64VecError {
+        WavF64VecError::SliceConversion(err)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for WavF64VecError {
+    fn from(err: std::string::FromUtf8Error) -> WavF64VecError {
+        WavF64VecError::Utf8(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WavF64VecErrorKind {
     PathIsNotFile,
     FileIsNotCompatibleFormat,
@@ -48,6 +150,7 @@ pub enum WavF64VecErrorKind {
     FormatIsNotSupported,
     BytesLengthError,
     AudioDataVecLengthError,
+    ParameterIsOutOfRange,
 }
 
 struct WavF64VecErrorMessage {
@@ -55,7 +158,7 @@ struct WavF64VecErrorMessage {
     message: &'static str,
 }
 
-const WAVE_AUDIO_ERR_MESSAGE: [WavF64VecErrorMessage; 9] = [
+const WAVE_AUDIO_ERR_MESSAGE: [WavF64VecErrorMessage; 10] = [
     WavF64VecErrorMessage {
         err_kind: WavF64VecErrorKind::PathIsNotFile,
         message: "Specified path is not file.",
@@ -92,4 +195,8 @@ const WAVE_AUDIO_ERR_MESSAGE: [WavF64VecErrorMessage; 9] = [
         err_kind: WavF64VecErrorKind::AudioDataVecLengthError,
         message: "Audio data vector length is too small or too large.",
     },
+    WavF64VecErrorMessage {
+        err_kind: WavF64VecErrorKind::ParameterIsOutOfRange,
+        message: "Specified parameter is out of range.",
+    },
 ];
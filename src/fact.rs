@@ -0,0 +1,97 @@
+use crate::error::*;
+use crate::{SubChunk, WavContainer, WavFile, WAVEFORMAT_ID_PCM};
+
+/// "fact" chunk body size.
+/// * 4 = sample length
+const FACT_CHUNK_MIN_SIZE: usize = 4;
+
+/// "fact" chunk. It is required by the formats other than PCM.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct FactChunk {
+    /// Number of samples of each channel. (frames)
+    pub sample_len: u32,
+}
+
+impl FactChunk {
+    /// Create structure.
+    pub fn new(sample_len: u32) -> FactChunk {
+        FactChunk { sample_len }
+    }
+
+    /// Create structure with the "fact" chunk.
+    pub fn from_sub_chunk(sub_chunk: &SubChunk) -> Result<FactChunk> {
+        let chunk_body = &sub_chunk.bytes_data_vec;
+        if sub_chunk.chunk_id != [b'f', b'a', b'c', b't'] || chunk_body.len() < FACT_CHUNK_MIN_SIZE {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"fact\"".to_string()),
+            ));
+        }
+        Ok(FactChunk {
+            sample_len: u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[0..4])?),
+        })
+    }
+
+    /// Make "fact" chunk.
+    pub fn to_sub_chunk(&self) -> SubChunk {
+        SubChunk {
+            chunk_id: [b'f', b'a', b'c', b't'],
+            bytes_data_vec: self.sample_len.to_le_bytes().to_vec(),
+        }
+    }
+}
+
+impl WavFile {
+    /// Get "fact" chunk. If it does not exist, return None.
+    pub fn get_fact(&self) -> Result<Option<FactChunk>> {
+        match self
+            .sub_chunks
+            .iter()
+            .find(|sub_chunk| sub_chunk.chunk_id == [b'f', b'a', b'c', b't'])
+        {
+            Some(sub_chunk) => Ok(Some(FactChunk::from_sub_chunk(sub_chunk)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Update "fact" chunk. If it does not exist, the chunk is added before "data" chunk.
+    pub fn update_fact(&mut self, fact_chunk: &FactChunk) -> Result<()> {
+        let fact_chunk = fact_chunk.to_sub_chunk();
+        if self.get_sub_chunk_idx(fact_chunk.chunk_id).is_some() {
+            return self.update_sub_chunk(fact_chunk);
+        }
+        self.precheck_sub_chunk_size(None, fact_chunk.bytes_data_vec.len(), "fact".to_string())?;
+        match self.get_sub_chunk_idx([b'd', b'a', b't', b'a']) {
+            Some(data_chunk_idx) => self.sub_chunks.insert(data_chunk_idx, fact_chunk),
+            None => self.sub_chunks.push(fact_chunk),
+        }
+        Ok(())
+    }
+
+    /// Make "fact" chunk of the number of samples of the new audio data.
+    /// The chunk is made when the format is not PCM, and an existing chunk is always refreshed. Return None if the chunk is not required.
+    /// The number of samples over 32bit is 0xFFFFFFFF for RF64 and BW64, which have the number in "ds64" chunk, otherwise an error.
+    pub(crate) fn make_fitted_fact_chunk(&self, format_id: usize, frame_len: usize) -> Result<Option<FactChunk>> {
+        if format_id == WAVEFORMAT_ID_PCM
+            && !self
+                .sub_chunks
+                .iter()
+                .any(|sub_chunk| sub_chunk.chunk_id == [b'f', b'a', b'c', b't'])
+        {
+            return Ok(None);
+        }
+        let sample_len = match u32::try_from(frame_len) {
+            Ok(sample_len) => sample_len,
+            Err(_) => match self.container {
+                WavContainer::Rf64 | WavContainer::Bw64 | WavContainer::RiffOrRf64 => u32::MAX,
+                _ => {
+                    return Err(WavF64VecError::new(
+                        WavF64VecErrorKind::SubChunkSizeTooLarge,
+                        Some("\"fact\"".to_string()),
+                    ));
+                }
+            },
+        };
+        Ok(Some(FactChunk::new(sample_len)))
+    }
+}
@@ -0,0 +1,70 @@
+/// End of each segment of A-law in 13bit.
+const ALAW_SEGMENT_END: [i32; 8] = [0x1f, 0x3f, 0x7f, 0xff, 0x1ff, 0x3ff, 0x7ff, 0xfff];
+/// End of each segment of mu-law in 14bit.
+const MULAW_SEGMENT_END: [i32; 8] = [0x3f, 0x7f, 0xff, 0x1ff, 0x3ff, 0x7ff, 0xfff, 0x1fff];
+/// Bias of mu-law in 16bit.
+const MULAW_BIAS: i32 = 0x84;
+/// Maximum of mu-law in 14bit.
+const MULAW_CLIP: i32 = 8159;
+
+/// Convert from a A-law (ITU-T G.711) code to the 16bit linear value.
+pub(crate) fn alaw_to_pcm(alaw_code: u8) -> i16 {
+    let alaw_code = i32::from(alaw_code ^ 0x55);
+    let segment = (alaw_code & 0x70) >> 4;
+    let mut pcm_val = (alaw_code & 0x0f) << 4;
+    match segment {
+        0 => pcm_val += 0x08,
+        1 => pcm_val += 0x108,
+        _ => pcm_val = (pcm_val + 0x108) << (segment - 1),
+    }
+    if alaw_code & 0x80 != 0 {
+        pcm_val as i16
+    } else {
+        -pcm_val as i16
+    }
+}
+
+/// Convert from the 16bit linear value to a A-law code.
+pub(crate) fn pcm_to_alaw(pcm_val: i16) -> u8 {
+    let mut pcm_val = i32::from(pcm_val) >> 3;
+    let mask = if pcm_val >= 0 {
+        0xd5
+    } else {
+        pcm_val = -pcm_val - 1;
+        0x55
+    };
+    let alaw_code = match ALAW_SEGMENT_END.iter().position(|segment_end| pcm_val <= *segment_end) {
+        Some(0) => (pcm_val >> 1) & 0x0f,
+        Some(segment) => ((segment as i32) << 4) | ((pcm_val >> segment) & 0x0f),
+        None => 0x7f,
+    };
+    alaw_code as u8 ^ mask
+}
+
+/// Convert from a mu-law (ITU-T G.711) code to the 16bit linear value.
+pub(crate) fn mulaw_to_pcm(mulaw_code: u8) -> i16 {
+    let mulaw_code = i32::from(!mulaw_code);
+    let pcm_val = (((mulaw_code & 0x0f) << 3) + MULAW_BIAS) << ((mulaw_code & 0x70) >> 4);
+    if mulaw_code & 0x80 != 0 {
+        (MULAW_BIAS - pcm_val) as i16
+    } else {
+        (pcm_val - MULAW_BIAS) as i16
+    }
+}
+
+/// Convert from the 16bit linear value to a mu-law code.
+pub(crate) fn pcm_to_mulaw(pcm_val: i16) -> u8 {
+    let mut pcm_val = i32::from(pcm_val) >> 2;
+    let mask = if pcm_val < 0 {
+        pcm_val = -pcm_val;
+        0x7f
+    } else {
+        0xff
+    };
+    pcm_val = pcm_val.min(MULAW_CLIP) + (MULAW_BIAS >> 2);
+    let mulaw_code = match MULAW_SEGMENT_END.iter().position(|segment_end| pcm_val <= *segment_end) {
+        Some(segment) => ((segment as i32) << 4) | ((pcm_val >> (segment + 1)) & 0x0f),
+        None => 0x7f,
+    };
+    mulaw_code as u8 ^ mask
+}
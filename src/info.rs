@@ -0,0 +1,189 @@
+use crate::error::*;
+use crate::{SubChunk, WavFile};
+
+/// Title. (Name)
+pub const INFO_ID_TITLE: [u8; 4] = [b'I', b'N', b'A', b'M'];
+/// Artist.
+pub const INFO_ID_ARTIST: [u8; 4] = [b'I', b'A', b'R', b'T'];
+/// Comment.
+pub const INFO_ID_COMMENT: [u8; 4] = [b'I', b'C', b'M', b'T'];
+/// Creation date. (e.g. "2024-01-31")
+pub const INFO_ID_CREATION_DATE: [u8; 4] = [b'I', b'C', b'R', b'D'];
+/// Software.
+pub const INFO_ID_SOFTWARE: [u8; 4] = [b'I', b'S', b'F', b'T'];
+/// Genre.
+pub const INFO_ID_GENRE: [u8; 4] = [b'I', b'G', b'N', b'R'];
+
+/// "INFO" list of "LIST" chunk.
+/// Entries are kept in the order of the chunk, including unknown ids.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct InfoList {
+    /// Entries of (id, text). The text is written as UTF-8 with a terminating null.
+    pub entry_vec: Vec<([u8; 4], String)>,
+}
+
+impl InfoList {
+    /// Create structure.
+    pub fn new() -> InfoList {
+        InfoList { entry_vec: Vec::new() }
+    }
+
+    /// Create structure with the "LIST" chunk. The list type must be "INFO".
+    /// Text which is not UTF-8 is read as Latin-1.
+    pub fn from_sub_chunk(sub_chunk: &SubChunk) -> Result<InfoList> {
+        let chunk_body = &sub_chunk.bytes_data_vec;
+        if sub_chunk.chunk_id != [b'L', b'I', b'S', b'T'] || chunk_body.get(0..4) != Some(&b"INFO"[..]) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("\"LIST\" is not \"INFO\"".to_string()),
+            ));
+        }
+        let mut entry_vec: Vec<([u8; 4], String)> = Vec::new();
+        // 4 = "INFO"
+        let mut pos = 4;
+        // 8 = id + text size
+        while chunk_body.len() - pos >= 8 {
+            let id = <[u8; 4]>::try_from(&chunk_body[pos..pos + 4])?;
+            let text_size = usize::try_from(u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[pos + 4..pos + 8])?))?;
+            let text_head = pos + 8;
+            if chunk_body.len() - text_head < text_size {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::SubChunkSizeError,
+                    Some(format!("\"{}\"", String::from_utf8_lossy(&id))),
+                ));
+            }
+            entry_vec.push((id, Self::decode_text(&chunk_body[text_head..text_head + text_size])));
+            // Odd-length text is followed by a pad byte.
+            pos = (text_head + text_size + text_size % 2).min(chunk_body.len());
+        }
+        Ok(InfoList { entry_vec })
+    }
+
+    /// Make "LIST" chunk.
+    pub fn to_sub_chunk(&self) -> SubChunk {
+        let mut chunk_body: Vec<u8> = b"INFO".to_vec();
+        for (id, text) in &self.entry_vec {
+            // Terminating null
+            let text_size = text.len() + 1;
+            chunk_body.extend_from_slice(id);
+            chunk_body.extend_from_slice(&(text_size as u32).to_le_bytes());
+            chunk_body.extend_from_slice(text.as_bytes());
+            chunk_body.push(0x00);
+            if text_size % 2 == 1 {
+                chunk_body.push(0x00);
+            }
+        }
+        SubChunk {
+            chunk_id: [b'L', b'I', b'S', b'T'],
+            bytes_data_vec: chunk_body,
+        }
+    }
+
+    /// Get the text of the id. If the id does not exist, return None.
+    pub fn get(&self, id: [u8; 4]) -> Option<&str> {
+        self.entry_vec
+            .iter()
+            .find(|(entry_id, _)| *entry_id == id)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Set the text of the id. If the id does not exist, the entry is added.
+    pub fn set(&mut self, id: [u8; 4], text: &str) {
+        match self.entry_vec.iter_mut().find(|(entry_id, _)| *entry_id == id) {
+            Some(entry) => entry.1 = text.to_string(),
+            None => self.entry_vec.push((id, text.to_string())),
+        }
+    }
+
+    /// Remove the entry of the id. If the entry was removed, return true.
+    pub fn remove(&mut self, id: [u8; 4]) -> bool {
+        let entry_len = self.entry_vec.len();
+        self.entry_vec.retain(|(entry_id, _)| *entry_id != id);
+        self.entry_vec.len() != entry_len
+    }
+
+    /// Get the title. ("INAM")
+    pub fn get_title(&self) -> Option<&str> {
+        self.get(INFO_ID_TITLE)
+    }
+
+    /// Set the title. ("INAM")
+    pub fn set_title(&mut self, text: &str) {
+        self.set(INFO_ID_TITLE, text);
+    }
+
+    /// Get the artist. ("IART")
+    pub fn get_artist(&self) -> Option<&str> {
+        self.get(INFO_ID_ARTIST)
+    }
+
+    /// Set the artist. ("IART")
+    pub fn set_artist(&mut self, text: &str) {
+        self.set(INFO_ID_ARTIST, text);
+    }
+
+    /// Get the comment. ("ICMT")
+    pub fn get_comment(&self) -> Option<&str> {
+        self.get(INFO_ID_COMMENT)
+    }
+
+    /// Set the comment. ("ICMT")
+    pub fn set_comment(&mut self, text: &str) {
+        self.set(INFO_ID_COMMENT, text);
+    }
+
+    /// Get the creation date. ("ICRD")
+    pub fn get_creation_date(&self) -> Option<&str> {
+        self.get(INFO_ID_CREATION_DATE)
+    }
+
+    /// Set the creation date. ("ICRD")
+    pub fn set_creation_date(&mut self, text: &str) {
+        self.set(INFO_ID_CREATION_DATE, text);
+    }
+
+    /// Get the software. ("ISFT")
+    pub fn get_software(&self) -> Option<&str> {
+        self.get(INFO_ID_SOFTWARE)
+    }
+
+    /// Set the software. ("ISFT")
+    pub fn set_software(&mut self, text: &str) {
+        self.set(INFO_ID_SOFTWARE, text);
+    }
+
+    /// Get the genre. ("IGNR")
+    pub fn get_genre(&self) -> Option<&str> {
+        self.get(INFO_ID_GENRE)
+    }
+
+    /// Set the genre. ("IGNR")
+    pub fn set_genre(&mut self, text: &str) {
+        self.set(INFO_ID_GENRE, text);
+    }
+
+    /// Decode the text without the terminating nulls.
+    fn decode_text(bytes: &[u8]) -> String {
+        let text_len = bytes.iter().rposition(|byte| *byte != 0x00).map_or(0, |idx| idx + 1);
+        match String::from_utf8(bytes[..text_len].to_vec()) {
+            Ok(text) => text,
+            // Latin-1
+            Err(_) => bytes[..text_len].iter().map(|byte| char::from(*byte)).collect(),
+        }
+    }
+}
+
+impl WavFile {
+    /// Get the "INFO" list. If "LIST" chunk of "INFO" does not exist, return None.
+    pub fn get_info_list(&self) -> Result<Option<InfoList>> {
+        match self.get_list_chunk_idx([b'I', b'N', b'F', b'O']) {
+            Some(chunk_idx) => Ok(Some(InfoList::from_sub_chunk(&self.sub_chunks[chunk_idx])?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Update "LIST" chunk of "INFO". If it does not exist, the chunk is added. Other "LIST" chunks (e.g. "adtl") are kept.
+    pub fn update_info_list(&mut self, info_list: &InfoList) -> Result<()> {
+        self.update_list_chunk(info_list.to_sub_chunk().bytes_data_vec)
+    }
+}
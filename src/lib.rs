@@ -1,28 +1,95 @@
 //! This libary provides the following features:
-//! * Reading & writing wav files.
+//! * Reading & writing wav files, and wav data in any reader, writer or bytes.
+//! * Streaming read & write of large wav files block by block (`WavReader` & `WavWriter`).
 //! * Interconversion between wav audio data and a tupple that has a wave format structure and a audio data vector(`Vec<Vec<f64>>`). The order of the audio data vector's (`Vec<Vec<f64>>`) dimensions can be specified by corresponding APIs in each.
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Cursor;
 use std::path::Path;
 use std::path::PathBuf;
 
 use std::fs::File;
 
+mod encode;
+use encode::Quantizer;
+pub use encode::{DecodeOptions, Dither, EncodeOptions, NoiseShaping};
+
+mod scaling;
+pub use scaling::PcmScaling;
+use scaling::*;
+
+mod adpcm;
+use adpcm::*;
+
+mod aiff;
+pub use aiff::{AiffContainer, AiffFile};
+
+mod bext;
+pub use bext::BextChunk;
+
+mod cue;
+pub use cue::CuePoint;
+
+mod g711;
+use g711::*;
+
+mod info;
+pub use info::{
+    InfoList, INFO_ID_ARTIST, INFO_ID_COMMENT, INFO_ID_CREATION_DATE, INFO_ID_GENRE, INFO_ID_SOFTWARE, INFO_ID_TITLE,
+};
+
 mod error;
-use error::*;
+pub use error::{Result, WavF64VecError, WavF64VecErrorKind};
+
+mod fact;
+pub use fact::FactChunk;
+
+mod reader;
+pub use reader::WavReader;
+use reader::*;
+
+mod repair;
+pub use repair::WavRepairWarning;
+
+mod resample;
+pub use resample::{resample_for_channel_data_vec, resample_for_data_channel_vec, ResampleOptions, ResampleQuality, Resampler};
+
+mod rf64;
+use rf64::*;
+
+mod rifx;
+use rifx::*;
+
+mod sampler;
+pub use sampler::{InstChunk, SampleLoop, SampleLoopType, SmplChunk};
+
+mod wave64;
+pub use wave64::W64_GUID_CHUNK_ID;
+use wave64::*;
+
+mod writer;
+pub use writer::WavWriter;
 
 mod tests;
 
 pub const WAVEFORMAT_ID_PCM: usize = 0x0001;
+pub const WAVEFORMAT_ID_MS_ADPCM: usize = 0x0002;
 pub const WAVEFORMAT_ID_IEEE_FLOAT: usize = 0x0003;
+pub const WAVEFORMAT_ID_ALAW: usize = 0x0006;
+pub const WAVEFORMAT_ID_MULAW: usize = 0x0007;
+pub const WAVEFORMAT_ID_IMA_ADPCM: usize = 0x0011;
 pub const WAVEFORMAT_ID_EXTENSIBLE: usize = 0xfffe;
 
+/// Maximum number of channels. ("fmt" chunk channel field is 16bit.)
+pub const CHANNEL_MAX: usize = 0xffff;
+
 const BIT8_WAVE_DIVISOR: i32 = 0x80;
 const BIT16_WAVE_DIVISOR: i32 = 0x8000;
 const BIT24_WAVE_DIVISOR: i32 = 0x800000;
 const BIT32_WAVE_DIVISOR: i64 = 0x80000000;
 
-/// Maximum of body size of the "data" chunk.
+/// Maximum of body size of the "data" chunk in "RIFF" container.
 /// * 12 = "RIFF" + RIFF Size + "WAVE"
 /// * 24 = "fmt" chunk minimum size
 /// * 8 = data chunk_id + body_size
@@ -34,6 +101,32 @@ const WAVEFORMATEXTENSIBLE_SUBTYPE_PCM_GUID_LEBYTES: [u8; 16] = [
 const WAVEFORMATEXTENSIBLE_SUBTYPE_IEEE_FLOAT_GUID_LEBYTES: [u8; 16] = [
     0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
 ];
+const WAVEFORMATEXTENSIBLE_SUBTYPE_ALAW_GUID_LEBYTES: [u8; 16] = [
+    0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+];
+const WAVEFORMATEXTENSIBLE_SUBTYPE_MULAW_GUID_LEBYTES: [u8; 16] = [
+    0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+];
+
+/// Speaker positions of the channel mask. (`dwChannelMask` of WAVE_FORMAT_EXTENSIBLE)
+pub const SPEAKER_FRONT_LEFT: u32 = 0x1;
+pub const SPEAKER_FRONT_RIGHT: u32 = 0x2;
+pub const SPEAKER_FRONT_CENTER: u32 = 0x4;
+pub const SPEAKER_LOW_FREQUENCY: u32 = 0x8;
+pub const SPEAKER_BACK_LEFT: u32 = 0x10;
+pub const SPEAKER_BACK_RIGHT: u32 = 0x20;
+pub const SPEAKER_FRONT_LEFT_OF_CENTER: u32 = 0x40;
+pub const SPEAKER_FRONT_RIGHT_OF_CENTER: u32 = 0x80;
+pub const SPEAKER_BACK_CENTER: u32 = 0x100;
+pub const SPEAKER_SIDE_LEFT: u32 = 0x200;
+pub const SPEAKER_SIDE_RIGHT: u32 = 0x400;
+pub const SPEAKER_TOP_CENTER: u32 = 0x800;
+pub const SPEAKER_TOP_FRONT_LEFT: u32 = 0x1000;
+pub const SPEAKER_TOP_FRONT_CENTER: u32 = 0x2000;
+pub const SPEAKER_TOP_FRONT_RIGHT: u32 = 0x4000;
+pub const SPEAKER_TOP_BACK_LEFT: u32 = 0x8000;
+pub const SPEAKER_TOP_BACK_CENTER: u32 = 0x10000;
+pub const SPEAKER_TOP_BACK_RIGHT: u32 = 0x20000;
 
 /// Wave audio format structure.
 #[allow(dead_code)]
@@ -52,7 +145,7 @@ pub struct WaveFormat {
 impl WaveFormat {
     /// Check the format is supported.
     pub fn format_check(wave_format: &WaveFormat) -> Result<()> {
-        if wave_format.channel < 1 || wave_format.channel > 2 {
+        if wave_format.channel < 1 || wave_format.channel > CHANNEL_MAX {
             return Err(WavF64VecError::new(
                 WavF64VecErrorKind::FormatIsNotSupported,
                 Some("channel number".to_string()),
@@ -64,7 +157,28 @@ impl WaveFormat {
                 Some("bit rate".to_string()),
             ));
         }
+        // A-law and mu-law are 8bit.
+        if (wave_format.id == WAVEFORMAT_ID_ALAW || wave_format.id == WAVEFORMAT_ID_MULAW) && wave_format.bits != 8 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("bit rate".to_string()),
+            ));
+        }
         Self::sampling_rate_check(wave_format.sampling_rate)?;
+        // Block size is a 16bit field of the "fmt" chunk.
+        if wave_format.channel * wave_format.bits / 8 > 0xffff {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("block size".to_string()),
+            ));
+        }
+        // Bytes per sec is a 32bit field of the "fmt" chunk.
+        if wave_format.channel * wave_format.sampling_rate * (wave_format.bits / 8) > 0xffffffff {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("bytes per sec".to_string()),
+            ));
+        }
         Ok(())
     }
 
@@ -79,6 +193,96 @@ impl WaveFormat {
     }
 }
 
+/// Additional fields of the WAVE_FORMAT_EXTENSIBLE "fmt" chunk.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WaveFormatExtension {
+    /// Valid bits per sample. Must be less than or equal to `bits` of `WaveFormat`.
+    pub valid_bits: usize,
+    /// Speaker positions of the channels. (`SPEAKER_*` constants are combined.)
+    pub channel_mask: u32,
+}
+
+impl WaveFormatExtension {
+    /// Create structure with all bits valid and the default speaker positions for the channel number.
+    pub fn new(wave_format: &WaveFormat) -> WaveFormatExtension {
+        WaveFormatExtension {
+            valid_bits: wave_format.bits,
+            channel_mask: Self::default_channel_mask(wave_format.channel),
+        }
+    }
+
+    /// Default speaker positions for the channel number. Channels over 8 are not assigned to any speaker.
+    pub fn default_channel_mask(channel: usize) -> u32 {
+        match channel {
+            1 => SPEAKER_FRONT_CENTER,
+            2 => SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT,
+            3 => SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_CENTER,
+            // Quad
+            4 => SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_BACK_LEFT | SPEAKER_BACK_RIGHT,
+            // 5.0
+            5 => SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_CENTER | SPEAKER_BACK_LEFT | SPEAKER_BACK_RIGHT,
+            // 5.1
+            6 => {
+                SPEAKER_FRONT_LEFT
+                    | SPEAKER_FRONT_RIGHT
+                    | SPEAKER_FRONT_CENTER
+                    | SPEAKER_LOW_FREQUENCY
+                    | SPEAKER_BACK_LEFT
+                    | SPEAKER_BACK_RIGHT
+            }
+            // 6.1
+            7 => {
+                SPEAKER_FRONT_LEFT
+                    | SPEAKER_FRONT_RIGHT
+                    | SPEAKER_FRONT_CENTER
+                    | SPEAKER_LOW_FREQUENCY
+                    | SPEAKER_BACK_LEFT
+                    | SPEAKER_BACK_RIGHT
+                    | SPEAKER_BACK_CENTER
+            }
+            // 7.1
+            8 => {
+                SPEAKER_FRONT_LEFT
+                    | SPEAKER_FRONT_RIGHT
+                    | SPEAKER_FRONT_CENTER
+                    | SPEAKER_LOW_FREQUENCY
+                    | SPEAKER_BACK_LEFT
+                    | SPEAKER_BACK_RIGHT
+                    | SPEAKER_SIDE_LEFT
+                    | SPEAKER_SIDE_RIGHT
+            }
+            _ => 0,
+        }
+    }
+
+    /// Check the extension is valid for the wave format.
+    pub fn extension_check(wave_format: &WaveFormat, extension: &WaveFormatExtension) -> Result<()> {
+        if extension.valid_bits == 0 || extension.valid_bits > wave_format.bits {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("valid bits".to_string()),
+            ));
+        }
+        if extension.channel_mask.count_ones() as usize > wave_format.channel {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("channel mask".to_string()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the WAVE_FORMAT_EXTENSIBLE "fmt" chunk is required for the wave format and the extension.
+    /// It is required when there are more than 2 channels, PCM has more than 16 bits per sample,
+    /// valid bits are fewer than bits per sample or the speaker positions are not the default.
+    pub fn is_required(wave_format: &WaveFormat, extension: &WaveFormatExtension) -> bool {
+        wave_format.channel > 2
+            || (wave_format.id == WAVEFORMAT_ID_PCM && wave_format.bits > 16)
+            || extension.valid_bits != wave_format.bits
+            || extension.channel_mask != Self::default_channel_mask(wave_format.channel)
+    }
+}
+
 /// RIFF sub chunk.
 #[allow(dead_code)]
 #[derive(Clone, PartialEq, Debug)]
@@ -98,13 +302,34 @@ impl SubChunk {
     }
 }
 
+/// Container of wav data.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WavContainer {
+    /// "RIFF". The file size is limited to 4GB.
+    Riff,
+    /// "RF64". 64bit sizes are in the "ds64" chunk.
+    Rf64,
+    /// "BW64". 64bit sizes are in the "ds64" chunk.
+    Bw64,
+    /// "RIFF" while the file size is within 4GB, otherwise "RF64". Only for writing.
+    RiffOrRf64,
+    /// Sony Wave64. Chunk ids are GUIDs, sizes are 64bit and chunks are aligned to 8 bytes.
+    /// Chunk ids are mapped to and from the RIFF chunk ids.
+    Wave64,
+    /// "RIFX". Big endian RIFF. The file size is limited to 4GB.
+    /// "fmt", "fact", "data", "cue ", "smpl", "bext" and "LIST" ("INFO" and "adtl") chunk bodies are converted to and from little endian, so `sub_chunks` have the same bodies as "RIFF".
+    Rifx,
+}
+
 /// Wav file structure.
 #[allow(dead_code)]
 #[derive(Clone, PartialEq, Debug)]
 pub struct WavFile {
     /// Path of wav file.
     pub file_path: PathBuf,
-    /// Sub chunk vec.
+    /// Container of wav file.
+    pub container: WavContainer,
+    /// Sub chunk vec. ("ds64" chunk is not included.)
     pub sub_chunks: Vec<SubChunk>,
 }
 
@@ -113,6 +338,7 @@ impl WavFile {
     pub fn new() -> WavFile {
         WavFile {
             file_path: PathBuf::new(),
+            container: WavContainer::Riff,
             sub_chunks: Vec::new(),
         }
     }
@@ -124,42 +350,126 @@ impl WavFile {
             return Err(WavF64VecError::new(WavF64VecErrorKind::PathIsNotFile, None));
         }
 
-        // -- Read Whole File --
-        let target_file = File::open(file_path)?;
-        let mut buf = Vec::new();
-        let file_size = BufReader::new(&target_file).read_to_end(&mut buf)?;
+        let mut wav_file = Self::from_reader(BufReader::new(File::open(file_path)?))?;
+        wav_file.file_path = file_path.to_path_buf();
+        Ok(wav_file)
+    }
 
-        // -- Get WavFile Construction --
-        // "RIFF"
-        if buf[0x00..0x04] != [b'R', b'I', b'F', b'F'] {
-            return Err(WavF64VecError::new(
-                WavF64VecErrorKind::FileIsNotCompatibleFormat,
-                Some("\"RIFF\"".to_string()),
-            ));
-        }
-        // RIFF Size
-        let riff_size = usize::try_from(u32::from_le_bytes(<[u8; 4]>::try_from(&buf[0x04..0x08])?))?;
-        if riff_size != file_size - 8 {
-            return Err(WavF64VecError::new(
-                WavF64VecErrorKind::FileIsNotCompatibleFormat,
-                Some("RIFF Size".to_string()),
-            ));
-        }
-        // "WAVE"
-        if buf[0x08..0x0c] != [b'W', b'A', b'V', b'E'] {
-            return Err(WavF64VecError::new(
-                WavF64VecErrorKind::FileIsNotCompatibleFormat,
-                Some("\"WAVE\"".to_string()),
-            ));
-        }
+    /// Create structure with the wav data read from the reader until EOF.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<WavFile> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Self::from_bytes(&buf)
+    }
 
-        let sub_chunks_vec = Self::extract_sub_chunks(buf[0x0c..].to_vec(), file_size - 12)?;
+    /// Create structure with the wav data in the bytes.
+    pub fn from_bytes(buf: &[u8]) -> Result<WavFile> {
+        let mut cursor = Cursor::new(buf);
+        let riff_header = read_riff_header(&mut cursor, None)?;
+        let sub_chunk_heads = read_sub_chunk_heads(&mut cursor, &riff_header, None)?;
+        let sub_chunks_vec = Self::extract_sub_chunks(buf, &sub_chunk_heads, riff_header.container)?;
         Ok(WavFile {
-            file_path: file_path.to_path_buf(),
+            file_path: PathBuf::new(),
+            container: riff_header.container,
             sub_chunks: sub_chunks_vec,
         })
     }
 
+    /// Open wav file in the lenient mode. Wrong RIFF size, placeholder sizes, truncated chunks and trailing bytes are repaired.
+    /// Return Value: (WavFile, `Vec<WavRepairWarning>`): The warnings describe what was repaired.
+    pub fn open_lenient(file_path: &Path) -> Result<(WavFile, Vec<WavRepairWarning>)> {
+        // -- Check Parameter --
+        if !file_path.is_file() {
+            return Err(WavF64VecError::new(WavF64VecErrorKind::PathIsNotFile, None));
+        }
+
+        let (mut wav_file, warnings) = Self::from_reader_lenient(BufReader::new(File::open(file_path)?))?;
+        wav_file.file_path = file_path.to_path_buf();
+        Ok((wav_file, warnings))
+    }
+
+    /// Create structure with the wav data read from the reader until EOF in the lenient mode.
+    pub fn from_reader_lenient<R: Read>(mut reader: R) -> Result<(WavFile, Vec<WavRepairWarning>)> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Self::from_bytes_lenient(&buf)
+    }
+
+    /// Create structure with the wav data in the bytes in the lenient mode.
+    pub fn from_bytes_lenient(buf: &[u8]) -> Result<(WavFile, Vec<WavRepairWarning>)> {
+        let mut warnings: Vec<WavRepairWarning> = Vec::new();
+        let mut cursor = Cursor::new(buf);
+        let riff_header = read_riff_header(&mut cursor, Some(&mut warnings))?;
+        let sub_chunk_heads = read_sub_chunk_heads(&mut cursor, &riff_header, Some(&mut warnings))?;
+        let sub_chunks_vec = Self::extract_sub_chunks(buf, &sub_chunk_heads, riff_header.container)?;
+        let mut wav_file = WavFile {
+            file_path: PathBuf::new(),
+            container: riff_header.container,
+            sub_chunks: sub_chunks_vec,
+        };
+
+        // Remove the incomplete block left by truncation.
+        if let Ok(Some(wave_format)) = wav_file.get_format() {
+            if is_adpcm_format(wave_format.id) {
+                wav_file.remove_adpcm_partial_block(&wave_format, &mut warnings)?;
+                return Ok((wav_file, warnings));
+            }
+            let block_size = wave_format.channel * wave_format.bits / 8;
+            if let Some(data_chunk_idx) = wav_file.get_sub_chunk_idx([b'd', b'a', b't', b'a']) {
+                let bytes_data_vec = &mut wav_file.sub_chunks[data_chunk_idx].bytes_data_vec;
+                if let Some(partial_size) = bytes_data_vec.len().checked_rem(block_size) {
+                    if partial_size > 0 {
+                        bytes_data_vec.truncate(bytes_data_vec.len() - partial_size);
+                        warnings.push(WavRepairWarning::PartialBlock {
+                            size: partial_size as u64,
+                        });
+                    }
+                }
+            }
+        }
+        Ok((wav_file, warnings))
+    }
+
+    /// Remove the incomplete ADPCM block of the truncated "data" chunk, and limit the number of samples in "fact" chunk to the remaining blocks.
+    /// The last block of a complete "data" chunk may be shorter than the block align, so it is kept.
+    fn remove_adpcm_partial_block(&mut self, wave_format: &WaveFormat, warnings: &mut Vec<WavRepairWarning>) -> Result<()> {
+        let is_truncated = warnings.iter().any(|warning| {
+            matches!(
+                warning,
+                WavRepairWarning::TruncatedChunk {
+                    chunk_id: [b'd', b'a', b't', b'a'],
+                    ..
+                } | WavRepairWarning::ChunkSizePlaceholder {
+                    chunk_id: [b'd', b'a', b't', b'a'],
+                    ..
+                }
+            )
+        });
+        let adpcm_format = match self.get_adpcm_format(wave_format) {
+            Ok(adpcm_format) => adpcm_format,
+            Err(_) => return Ok(()),
+        };
+        let data_chunk_idx = match self.get_sub_chunk_idx([b'd', b'a', b't', b'a']) {
+            Some(data_chunk_idx) => data_chunk_idx,
+            None => return Ok(()),
+        };
+        let bytes_data_vec = &mut self.sub_chunks[data_chunk_idx].bytes_data_vec;
+        let partial_size = bytes_data_vec.len() % adpcm_format.block_align;
+        if !is_truncated || partial_size == 0 {
+            return Ok(());
+        }
+        bytes_data_vec.truncate(bytes_data_vec.len() - partial_size);
+        warnings.push(WavRepairWarning::PartialBlock {
+            size: partial_size as u64,
+        });
+        let block_len = bytes_data_vec.len() / adpcm_format.block_align;
+        let sample_len = u32::try_from(block_len * adpcm_format.samples_per_block).unwrap_or(u32::MAX);
+        match self.get_fact() {
+            Ok(Some(fact_chunk)) if fact_chunk.sample_len <= sample_len => Ok(()),
+            _ => self.update_fact(&FactChunk::new(sample_len)),
+        }
+    }
+
     /// Save self to wav file.
     pub fn save(&mut self) -> Result<()> {
         let file_path = self.file_path.clone();
@@ -169,57 +479,190 @@ impl WavFile {
 
     /// Save self to wav file as argument path.
     pub fn save_as(&mut self, file_path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(file_path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+
+        // Update Self Infomation
+        self.file_path = file_path.to_path_buf();
+        Ok(())
+    }
+
+    /// Write self as wav data to the writer.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
         // -- Check Parameter --
-        let mut buf: Vec<u8> = Vec::new();
-        buf.append(&mut [b'R', b'I', b'F', b'F'].to_vec());
-        let mut riff_size: usize = 4;
+        let mut chunks_size: u64 = 0;
         for sub_chunk in &self.sub_chunks {
-            if let Some(new_riff_size) = riff_size.checked_add(sub_chunk.bytes_data_vec.len() + 8) {
-                riff_size = new_riff_size;
+            // 8 = chunk_id + body_size, odd-length body is followed by a pad byte.
+            let body_size = sub_chunk.bytes_data_vec.len() as u64;
+            if let Some(new_chunks_size) = chunks_size.checked_add(8 + body_size + body_size % 2) {
+                chunks_size = new_chunks_size;
             } else {
                 return Err(WavF64VecError::new(WavF64VecErrorKind::SubChunkSizeTooLarge, None));
             }
         }
-        if riff_size > 0xffffffff - 8 {
-            return Err(WavF64VecError::new(WavF64VecErrorKind::SubChunkSizeTooLarge, None));
+        // 4 = "WAVE"
+        let container = match self.container {
+            WavContainer::RiffOrRf64 if 4 + chunks_size > 0xffffffff - 8 => WavContainer::Rf64,
+            WavContainer::RiffOrRf64 => WavContainer::Riff,
+            container => container,
+        };
+
+        if container == WavContainer::Wave64 {
+            let mut riff_size = W64_HEADER_SIZE;
+            for sub_chunk in &self.sub_chunks {
+                let (_, chunk_body) = w64_chunk_guid_and_body(sub_chunk.chunk_id, &sub_chunk.bytes_data_vec);
+                let body_size = chunk_body.len() as u64;
+                riff_size += W64_CHUNK_HEAD_SIZE + body_size + w64_padding_size(body_size);
+            }
+            writer.write_all(&W64_RIFF_GUID)?;
+            writer.write_all(&riff_size.to_le_bytes())?;
+            writer.write_all(&W64_WAVE_GUID)?;
+            for sub_chunk in &self.sub_chunks {
+                let (guid, chunk_body) = w64_chunk_guid_and_body(sub_chunk.chunk_id, &sub_chunk.bytes_data_vec);
+                let body_size = chunk_body.len() as u64;
+                writer.write_all(&guid)?;
+                writer.write_all(&(W64_CHUNK_HEAD_SIZE + body_size).to_le_bytes())?;
+                writer.write_all(chunk_body)?;
+                writer.write_all(&vec![0x00; w64_padding_size(body_size) as usize])?;
+            }
+        } else if container == WavContainer::Rifx {
+            let riff_size = 4 + chunks_size;
+            if riff_size > 0xffffffff - 8 {
+                return Err(WavF64VecError::new(WavF64VecErrorKind::SubChunkSizeTooLarge, None));
+            }
+            let op_wave_format = self.get_format().ok().flatten();
+            writer.write_all(b"RIFX")?;
+            writer.write_all(&(riff_size as u32).to_be_bytes())?;
+            writer.write_all(b"WAVE")?;
+            for sub_chunk in &self.sub_chunks {
+                let chunk_body = rifx_chunk_body(sub_chunk, op_wave_format.as_ref())?;
+                writer.write_all(&sub_chunk.chunk_id)?;
+                writer.write_all(&(chunk_body.len() as u32).to_be_bytes())?;
+                writer.write_all(&chunk_body)?;
+                Self::write_pad_byte(&mut writer, chunk_body.len())?;
+            }
+        } else if container == WavContainer::Riff {
+            let riff_size = 4 + chunks_size;
+            if riff_size > 0xffffffff - 8 {
+                return Err(WavF64VecError::new(WavF64VecErrorKind::SubChunkSizeTooLarge, None));
+            }
+            writer.write_all(b"RIFF")?;
+            writer.write_all(&riff_size.to_le_bytes()[0..4])?;
+            writer.write_all(b"WAVE")?;
+            for sub_chunk in &self.sub_chunks {
+                writer.write_all(&sub_chunk.chunk_id)?;
+                writer.write_all(&sub_chunk.bytes_data_vec.len().to_le_bytes()[0..4])?;
+                writer.write_all(&sub_chunk.bytes_data_vec)?;
+                Self::write_pad_byte(&mut writer, sub_chunk.bytes_data_vec.len())?;
+            }
+        } else {
+            let mut ds64 = Ds64::default();
+            for sub_chunk in &self.sub_chunks {
+                let chunk_size = sub_chunk.bytes_data_vec.len() as u64;
+                if sub_chunk.chunk_id == [b'd', b'a', b't', b'a'] {
+                    ds64.data_size = chunk_size;
+                } else if chunk_size >= u64::from(RF64_SIZE_PLACEHOLDER) {
+                    ds64.table.push((sub_chunk.chunk_id, chunk_size));
+                }
+            }
+            if let Ok(Some(wave_format)) = self.get_format() {
+                ds64.sample_count = self.get_ds64_sample_count(&wave_format, ds64.data_size);
+            }
+            // 12 = "ds64" chunk_id + body_size + table length
+            let ds64_chunk_size = 8 + DS64_CHUNK_MIN_SIZE as u64 + 12 * ds64.table.len() as u64;
+            ds64.riff_size = 4 + ds64_chunk_size + chunks_size;
+            let ds64_chunk_body = ds64.to_chunk();
+
+            if container == WavContainer::Bw64 {
+                writer.write_all(b"BW64")?;
+            } else {
+                writer.write_all(b"RF64")?;
+            }
+            writer.write_all(&RF64_SIZE_PLACEHOLDER.to_le_bytes())?;
+            writer.write_all(b"WAVE")?;
+            writer.write_all(b"ds64")?;
+            writer.write_all(&ds64_chunk_body.len().to_le_bytes()[0..4])?;
+            writer.write_all(&ds64_chunk_body)?;
+            for sub_chunk in &self.sub_chunks {
+                writer.write_all(&sub_chunk.chunk_id)?;
+                if sub_chunk.chunk_id == [b'd', b'a', b't', b'a']
+                    || sub_chunk.bytes_data_vec.len() as u64 >= u64::from(RF64_SIZE_PLACEHOLDER)
+                {
+                    writer.write_all(&RF64_SIZE_PLACEHOLDER.to_le_bytes())?;
+                } else {
+                    writer.write_all(&sub_chunk.bytes_data_vec.len().to_le_bytes()[0..4])?;
+                }
+                writer.write_all(&sub_chunk.bytes_data_vec)?;
+                Self::write_pad_byte(&mut writer, sub_chunk.bytes_data_vec.len())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the number of samples per channel of "data" chunk for "ds64" chunk.
+    /// ADPCM has the samples per block of "fmt" chunk, and the fill of the last block is excluded by "fact" chunk.
+    fn get_ds64_sample_count(&self, wave_format: &WaveFormat, data_size: u64) -> u64 {
+        if !is_adpcm_format(wave_format.id) {
+            let block_size = (wave_format.channel * wave_format.bits / 8) as u64;
+            return data_size.checked_div(block_size).unwrap_or(0);
         }
-        buf.append(&mut riff_size.to_le_bytes()[0..4].to_vec());
-        buf.append(&mut [b'W', b'A', b'V', b'E'].to_vec());
-        for sub_chunk in &mut self.sub_chunks {
-            buf.append(&mut sub_chunk.chunk_id.to_vec());
-            buf.append(&mut sub_chunk.bytes_data_vec.len().to_le_bytes()[0..4].to_vec());
-            buf.append(&mut sub_chunk.bytes_data_vec.clone());
+        let adpcm_format = match self.get_adpcm_format(wave_format) {
+            Ok(adpcm_format) => adpcm_format,
+            Err(_) => return 0,
+        };
+        let sample_count = data_size.div_ceil(adpcm_format.block_align as u64) * adpcm_format.samples_per_block as u64;
+        match self.get_fact() {
+            // 0xFFFFFFFF is the placeholder of RF64.
+            Ok(Some(fact_chunk)) if fact_chunk.sample_len != u32::MAX => sample_count.min(u64::from(fact_chunk.sample_len)),
+            _ => sample_count,
         }
-        let mut target_file = File::create(file_path)?;
-        target_file.write_all(&buf)?;
+    }
 
-        // Update Self Infomation
-        self.file_path = file_path.to_path_buf();
+    /// Write a pad byte if the chunk body size is odd. (Chunks are word aligned.)
+    fn write_pad_byte<W: Write>(writer: &mut W, body_size: usize) -> Result<()> {
+        if body_size % 2 == 1 {
+            writer.write_all(&[0x00])?;
+        }
         Ok(())
     }
 
-    fn extract_sub_chunks(buf: Vec<u8>, chunks_size: usize) -> Result<Vec<SubChunk>> {
+    /// Get self as wav data bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf: Vec<u8> = Vec::new();
+        self.write_to(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn extract_sub_chunks(buf: &[u8], sub_chunk_heads: &[SubChunkHead], container: WavContainer) -> Result<Vec<SubChunk>> {
         let mut sub_chunks_vec: Vec<SubChunk> = Vec::new();
-        let mut chunk_head_addr: usize = 0x00;
-        while chunks_size - chunk_head_addr >= 8 {
-            let chunk_head_buf = &buf[chunk_head_addr..];
-            let chunk_body_size = usize::try_from(u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_head_buf[0x04..0x08])?))?;
-            if chunk_head_buf.len() < chunk_body_size + 8 {
-                return Err(WavF64VecError::new(WavF64VecErrorKind::SubChunkSizeError, None));
+        for sub_chunk_head in sub_chunk_heads {
+            let body_head = usize::try_from(sub_chunk_head.body_head)?;
+            let body_size = usize::try_from(sub_chunk_head.body_size)?;
+            let mut bytes_data_vec: Vec<u8> = Vec::new();
+            if let Some(guid) = sub_chunk_head.op_guid {
+                bytes_data_vec.extend_from_slice(&guid);
             }
+            bytes_data_vec.extend_from_slice(&buf[body_head..body_head + body_size]);
             let sub_chunk = SubChunk {
-                chunk_id: [
-                    chunk_head_buf[0x00],
-                    chunk_head_buf[0x01],
-                    chunk_head_buf[0x02],
-                    chunk_head_buf[0x03],
-                ],
-                bytes_data_vec: chunk_head_buf[8..(chunk_body_size + 8)].to_vec(),
+                chunk_id: sub_chunk_head.chunk_id,
+                bytes_data_vec,
             };
             sub_chunks_vec.push(sub_chunk);
-            chunk_head_addr += 8 + chunk_body_size;
         }
-        return Ok(sub_chunks_vec);
+        if container == WavContainer::Rifx {
+            // The samples of "data" chunk can not be converted.
+            swap_sub_chunks_from_rifx(&mut sub_chunks_vec).map_err(|err| {
+                match sub_chunk_heads
+                    .iter()
+                    .find(|sub_chunk_head| sub_chunk_head.chunk_id == [b'd', b'a', b't', b'a'])
+                {
+                    Some(sub_chunk_head) => err.at(sub_chunk_head.chunk_id, Some(sub_chunk_head.head_addr)),
+                    None => err,
+                }
+            })?;
+        }
+        Ok(sub_chunks_vec)
     }
 
     /// Get WaveFormat
@@ -235,7 +678,12 @@ impl WavFile {
         Ok(None)
     }
 
+    /// Parse "fmt" chunk body. The errors have the chunk id "fmt ".
     fn get_format_from_chunk(chunk_body: &Vec<u8>) -> Result<WaveFormat> {
+        Self::parse_format_chunk(chunk_body).map_err(|err| err.at([b'f', b'm', b't', b' '], None))
+    }
+
+    fn parse_format_chunk(chunk_body: &Vec<u8>) -> Result<WaveFormat> {
         // format id
         if chunk_body.len() < 0x10 {
             return Err(WavF64VecError::new(
@@ -246,7 +694,11 @@ impl WavFile {
         let mut format_id = usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x00..0x02])?));
         match format_id {
             WAVEFORMAT_ID_PCM => {}
+            WAVEFORMAT_ID_MS_ADPCM => {}
             WAVEFORMAT_ID_IEEE_FLOAT => {}
+            WAVEFORMAT_ID_ALAW => {}
+            WAVEFORMAT_ID_MULAW => {}
+            WAVEFORMAT_ID_IMA_ADPCM => {}
             WAVEFORMAT_ID_EXTENSIBLE => {
                 if chunk_body.len() < 0x28 {
                     return Err(WavF64VecError::new(
@@ -261,6 +713,10 @@ impl WavFile {
                     waveextensible_subtype_guid = &WAVEFORMATEXTENSIBLE_SUBTYPE_PCM_GUID_LEBYTES;
                 } else if format_id == WAVEFORMAT_ID_IEEE_FLOAT {
                     waveextensible_subtype_guid = &WAVEFORMATEXTENSIBLE_SUBTYPE_IEEE_FLOAT_GUID_LEBYTES;
+                } else if format_id == WAVEFORMAT_ID_ALAW {
+                    waveextensible_subtype_guid = &WAVEFORMATEXTENSIBLE_SUBTYPE_ALAW_GUID_LEBYTES;
+                } else if format_id == WAVEFORMAT_ID_MULAW {
+                    waveextensible_subtype_guid = &WAVEFORMATEXTENSIBLE_SUBTYPE_MULAW_GUID_LEBYTES;
                 } else {
                     return Err(WavF64VecError::new(
                         WavF64VecErrorKind::SubChunkSizeError,
@@ -295,6 +751,18 @@ impl WavFile {
         // Bit Rate
         let bits = usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x0e..0x10])?));
 
+        let wave_format = WaveFormat {
+            id: format_id,
+            channel,
+            sampling_rate,
+            bits,
+        };
+        if is_adpcm_format(format_id) {
+            // Check the block layout instead. Byte Per Sec of ADPCM is approximate.
+            AdpcmFormat::from_format_chunk(&wave_format, chunk_body)?;
+            return Ok(wave_format);
+        }
+
         // Check Byte Per Sec.
         if bytes_per_sec != channel * sampling_rate * (bits / 8) {
             return Err(WavF64VecError::new(
@@ -309,19 +777,90 @@ impl WavFile {
                 Some("block size".to_string()),
             ));
         }
-        Ok(WaveFormat {
-            id: format_id,
-            channel: channel,
-            sampling_rate: sampling_rate,
-            bits: bits,
-        })
+        Ok(wave_format)
+    }
+
+    /// Get the additional fields of the WAVE_FORMAT_EXTENSIBLE "fmt" chunk. If the "fmt" chunk is not extensible, returns None.
+    pub fn get_format_extension(&self) -> Result<Option<WaveFormatExtension>> {
+        for sub_chunk in &self.sub_chunks {
+            if sub_chunk.chunk_id == [b'f', b'm', b't', b' '] {
+                // Check the whole chunk
+                Self::get_format_from_chunk(&sub_chunk.bytes_data_vec)?;
+                return Self::get_format_extension_from_chunk(&sub_chunk.bytes_data_vec);
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_format_extension_from_chunk(chunk_body: &[u8]) -> Result<Option<WaveFormatExtension>> {
+        if usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x00..0x02])?)) != WAVEFORMAT_ID_EXTENSIBLE {
+            return Ok(None);
+        }
+        // Valid Bits
+        let valid_bits = usize::from(u16::from_le_bytes(<[u8; 2]>::try_from(&chunk_body[0x12..0x14])?));
+        // Channel Mask
+        let channel_mask = u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[0x14..0x18])?);
+        Ok(Some(WaveFormatExtension {
+            valid_bits,
+            channel_mask,
+        }))
+    }
+
+    /// Update the additional fields of the WAVE_FORMAT_EXTENSIBLE "fmt" chunk. The "fmt" chunk is rewritten as extensible.
+    pub fn update_format_extension(&mut self, extension: &WaveFormatExtension) -> Result<()> {
+        let op_chunk_idx = self.get_sub_chunk_idx([b'f', b'm', b't', b' ']);
+        if let Some(chunk_idx) = op_chunk_idx {
+            let wave_format = Self::get_format_from_chunk(&self.sub_chunks[chunk_idx].bytes_data_vec)?;
+            WaveFormatExtension::extension_check(&wave_format, extension)?;
+            let format_buf = Self::set_format_with_extension(&wave_format, Some(extension))?;
+            self.precheck_sub_chunk_size(op_chunk_idx, format_buf.len(), "fmt ".to_string())?;
+            self.sub_chunks[chunk_idx].bytes_data_vec = format_buf;
+            Ok(())
+        } else {
+            Err(WavF64VecError::new(
+                WavF64VecErrorKind::NoRequiredSubChunk,
+                Some("\"fmt\"".to_string()),
+            ))
+        }
     }
 
     fn set_format(&self, wave_format: &WaveFormat) -> Result<Vec<u8>> {
+        // Keep the speaker positions of the existing extension while the channel number does not change.
+        // The valid bits are kept only for the same sample format.
+        if let Ok(Some(mut existing_extension)) = self.get_format_extension() {
+            if let Ok(Some(existing_format)) = self.get_format() {
+                if existing_format.id != wave_format.id || existing_format.bits != wave_format.bits {
+                    existing_extension.valid_bits = wave_format.bits;
+                }
+                if existing_format.channel == wave_format.channel
+                    && WaveFormatExtension::extension_check(wave_format, &existing_extension).is_ok()
+                {
+                    return Self::set_format_with_required_extension(wave_format, &existing_extension);
+                }
+            }
+        }
+        Self::set_format_with_required_extension(wave_format, &WaveFormatExtension::new(wave_format))
+    }
+
+    /// Make "fmt" chunk body. The extension is written only when it is required.
+    fn set_format_with_required_extension(wave_format: &WaveFormat, extension: &WaveFormatExtension) -> Result<Vec<u8>> {
+        WaveFormat::format_check(wave_format)?;
+        if WaveFormatExtension::is_required(wave_format, extension) {
+            Self::set_format_with_extension(wave_format, Some(extension))
+        } else {
+            Self::set_format_with_extension(wave_format, None)
+        }
+    }
+
+    fn set_format_with_extension(wave_format: &WaveFormat, op_extension: Option<&WaveFormatExtension>) -> Result<Vec<u8>> {
         // fmt chunk
         let mut chunk_body: Vec<u8> = Vec::new();
         // format id
-        chunk_body.append(&mut wave_format.id.to_le_bytes()[0..2].to_vec());
+        if op_extension.is_some() {
+            chunk_body.append(&mut WAVEFORMAT_ID_EXTENSIBLE.to_le_bytes()[0..2].to_vec());
+        } else {
+            chunk_body.append(&mut wave_format.id.to_le_bytes()[0..2].to_vec());
+        }
         // channel
         chunk_body.append(&mut wave_format.channel.to_le_bytes()[0..2].to_vec());
         // Sampling Rate
@@ -333,23 +872,102 @@ impl WavFile {
         chunk_body.append(&mut (wave_format.channel * wave_format.bits / 8).to_le_bytes()[0..2].to_vec());
         // Bit Rate
         chunk_body.append(&mut wave_format.bits.to_le_bytes()[0..2].to_vec());
+        if let Some(extension) = op_extension {
+            let waveextensible_subtype_guid = match wave_format.id {
+                WAVEFORMAT_ID_PCM => &WAVEFORMATEXTENSIBLE_SUBTYPE_PCM_GUID_LEBYTES,
+                WAVEFORMAT_ID_IEEE_FLOAT => &WAVEFORMATEXTENSIBLE_SUBTYPE_IEEE_FLOAT_GUID_LEBYTES,
+                WAVEFORMAT_ID_ALAW => &WAVEFORMATEXTENSIBLE_SUBTYPE_ALAW_GUID_LEBYTES,
+                WAVEFORMAT_ID_MULAW => &WAVEFORMATEXTENSIBLE_SUBTYPE_MULAW_GUID_LEBYTES,
+                _ => {
+                    return Err(WavF64VecError::new(
+                        WavF64VecErrorKind::FormatIsNotSupported,
+                        Some("format id".to_string()),
+                    ));
+                }
+            };
+            // Extension Size
+            chunk_body.append(&mut 22u16.to_le_bytes().to_vec());
+            // Valid Bits
+            chunk_body.append(&mut extension.valid_bits.to_le_bytes()[0..2].to_vec());
+            // Channel Mask
+            chunk_body.append(&mut extension.channel_mask.to_le_bytes().to_vec());
+            // Sub Format
+            chunk_body.append(&mut waveextensible_subtype_guid.to_vec());
+        } else if wave_format.id == WAVEFORMAT_ID_ALAW || wave_format.id == WAVEFORMAT_ID_MULAW {
+            // Extension Size
+            chunk_body.append(&mut 0u16.to_le_bytes().to_vec());
+        }
         Ok(chunk_body)
     }
 
     /// Get audio data. Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
     pub fn get_audio_for_channel_data_vec(&self) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
+        self.get_audio_for_channel_data_vec_with_options(&DecodeOptions::default())
+    }
+
+    /// Get audio data with the scaling of the options. Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
+    pub fn get_audio_for_channel_data_vec_with_options(&self, options: &DecodeOptions) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
         let (wave_format, bytes_data) = self.get_bytes_audio()?;
-        let channel_data_vec = Self::to_channel_data_vec(&wave_format, bytes_data)?;
+        if is_adpcm_format(wave_format.id) {
+            let channel_data_vec = self.decode_adpcm(&wave_format, &bytes_data, options)?;
+            return Ok((wave_format, channel_data_vec));
+        }
+        let channel_data_vec = Self::to_channel_data_vec(&wave_format, bytes_data, options)?;
         Ok((wave_format, channel_data_vec))
     }
 
     /// Get audio data. Return Value: `Vec<Vec<f64>>`: Outer is data vec. Inner is channel vec.
     pub fn get_audio_for_data_channel_vec(&self) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
+        self.get_audio_for_data_channel_vec_with_options(&DecodeOptions::default())
+    }
+
+    /// Get audio data with the scaling of the options. Return Value: `Vec<Vec<f64>>`: Outer is data vec. Inner is channel vec.
+    pub fn get_audio_for_data_channel_vec_with_options(&self, options: &DecodeOptions) -> Result<(WaveFormat, Vec<Vec<f64>>)> {
         let (wave_format, bytes_data) = self.get_bytes_audio()?;
-        let data_channel_vec = Self::to_data_channel_vec(&wave_format, bytes_data)?;
+        if is_adpcm_format(wave_format.id) {
+            let channel_data_vec = self.decode_adpcm(&wave_format, &bytes_data, options)?;
+            let data_channel_vec = (0..channel_data_vec[0].len())
+                .map(|data_idx| channel_data_vec.iter().map(|data_vec| data_vec[data_idx]).collect())
+                .collect();
+            return Ok((wave_format, data_channel_vec));
+        }
+        let data_channel_vec = Self::to_data_channel_vec(&wave_format, bytes_data, options)?;
         Ok((wave_format, data_channel_vec))
     }
 
+    /// Get the block align, the samples per block and the coefficients of ADPCM from "fmt" chunk.
+    fn get_adpcm_format(&self, wave_format: &WaveFormat) -> Result<AdpcmFormat> {
+        match self
+            .sub_chunks
+            .iter()
+            .find(|sub_chunk| sub_chunk.chunk_id == [b'f', b'm', b't', b' '])
+        {
+            Some(format_chunk) => AdpcmFormat::from_format_chunk(wave_format, &format_chunk.bytes_data_vec),
+            None => Err(WavF64VecError::new(
+                WavF64VecErrorKind::NoRequiredSubChunk,
+                Some("\"fmt\"".to_string()),
+            )),
+        }
+    }
+
+    /// Decode ADPCM blocks. Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
+    fn decode_adpcm(&self, wave_format: &WaveFormat, bytes_data_vec: &[u8], options: &DecodeOptions) -> Result<Vec<Vec<f64>>> {
+        let adpcm_format = self.get_adpcm_format(wave_format)?;
+        let mut channel_pcm_vec = adpcm_format.decode(bytes_data_vec)?;
+        // The fill of the last block is removed by the number of samples in "fact" chunk.
+        if let Some(fact_chunk) = self.get_fact()? {
+            let sample_len = usize::try_from(fact_chunk.sample_len)?;
+            for pcm_vec in &mut channel_pcm_vec {
+                pcm_vec.truncate(sample_len);
+            }
+        }
+        let full_scale = options.scaling.full_scale(16)?;
+        Ok(channel_pcm_vec
+            .iter()
+            .map(|pcm_vec| pcm_vec.iter().map(|pcm_val| f64::from(*pcm_val) / full_scale).collect())
+            .collect())
+    }
+
     fn get_bytes_audio(&self) -> Result<(WaveFormat, Vec<u8>)> {
         let mut op_wave_format: Option<WaveFormat> = None;
         let mut op_bytes_data: Option<Vec<u8>> = None;
@@ -393,7 +1011,7 @@ impl WavFile {
         }
     }
 
-    fn to_channel_data_vec(wave_format: &WaveFormat, bytes_data_vec: Vec<u8>) -> Result<Vec<Vec<f64>>> {
+    fn to_channel_data_vec(wave_format: &WaveFormat, bytes_data_vec: Vec<u8>, options: &DecodeOptions) -> Result<Vec<Vec<f64>>> {
         WaveFormat::format_check(wave_format)?;
         let mut channel_data_vec = Vec::new();
 
@@ -405,13 +1023,17 @@ impl WavFile {
         for (pos, _) in bytes_data_vec.iter().enumerate().step_by(step) {
             for channel_idx in 0..wave_format.channel {
                 let stt = pos + channel_idx * size;
-                channel_data_vec[channel_idx].push(bytes_to_f64wave(wave_format.id, &bytes_data_vec[stt..stt + size])?);
+                channel_data_vec[channel_idx].push(bytes_to_f64wave_with_scaling(
+                    wave_format.id,
+                    &bytes_data_vec[stt..stt + size],
+                    &options.scaling,
+                )?);
             }
         }
         Ok(channel_data_vec)
     }
 
-    fn to_data_channel_vec(wave_format: &WaveFormat, bytes_data_vec: Vec<u8>) -> Result<Vec<Vec<f64>>> {
+    fn to_data_channel_vec(wave_format: &WaveFormat, bytes_data_vec: Vec<u8>, options: &DecodeOptions) -> Result<Vec<Vec<f64>>> {
         WaveFormat::format_check(wave_format)?;
         let mut data_channel_vec = Vec::new();
 
@@ -422,57 +1044,178 @@ impl WavFile {
             let mut channel_vec = Vec::new();
             for channel_idx in 0..wave_format.channel {
                 let stt = pos + channel_idx * size;
-                channel_vec.push(bytes_to_f64wave(wave_format.id, &bytes_data_vec[stt..stt + size])?);
+                channel_vec.push(bytes_to_f64wave_with_scaling(
+                    wave_format.id,
+                    &bytes_data_vec[stt..stt + size],
+                    &options.scaling,
+                )?);
             }
             data_channel_vec.push(channel_vec);
         }
         Ok(data_channel_vec)
     }
 
-    /// Update audio data (update "fmt" and "data" chunk). If "fmt" or "data" argument chunk do not exist, those chunks are added.
+    /// Update audio data (update "fmt" and "data" chunk, and "fact" chunk for the formats other than PCM). If "fmt" or "data" argument chunk do not exist, those chunks are added.
     /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is channel vec. Inner is data vec.
     pub fn update_audio_for_channel_data_vec(
         &mut self,
         wave_format: &WaveFormat,
         channel_data_vec: &Vec<Vec<f64>>,
     ) -> Result<()> {
+        self.update_audio_for_channel_data_vec_with_options(wave_format, channel_data_vec, &EncodeOptions::default())
+    }
+
+    /// Update audio data with the dither and the noise shaping of the options.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is channel vec. Inner is data vec.
+    pub fn update_audio_for_channel_data_vec_with_options(
+        &mut self,
+        wave_format: &WaveFormat,
+        channel_data_vec: &Vec<Vec<f64>>,
+        options: &EncodeOptions,
+    ) -> Result<()> {
+        let (format_buf, bytes_data_vec) = if is_adpcm_format(wave_format.id) {
+            let adpcm_format = AdpcmFormat::new(wave_format, options.op_block_align)?;
+            let pcm_format = Self::adpcm_source_format(wave_format);
+            let pcm_bytes_data_vec = Self::from_channel_data_vec_with_options(&pcm_format, channel_data_vec, options)?;
+            Self::encode_adpcm_audio(&adpcm_format, &pcm_bytes_data_vec)
+        } else {
+            let bytes_data_vec = Self::from_channel_data_vec_with_options(wave_format, channel_data_vec, options)?;
+            (self.set_format(wave_format)?, bytes_data_vec)
+        };
+        self.update_audio_and_fit_chunks(wave_format.id, format_buf, bytes_data_vec, channel_data_vec[0].len())
+    }
+
+    /// Update audio data (update "fmt" and "data" chunk, and "fact" chunk for the formats other than PCM). If "fmt" or "data" argument chunk do not exist, those chunks are added.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is data vec. Inner is channel vec.
+    pub fn update_audio_for_data_channel_vec(
+        &mut self,
+        wave_format: &WaveFormat,
+        data_channel_vec: &Vec<Vec<f64>>,
+    ) -> Result<()> {
+        self.update_audio_for_data_channel_vec_with_options(wave_format, data_channel_vec, &EncodeOptions::default())
+    }
+
+    /// Update audio data with the dither and the noise shaping of the options.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is data vec. Inner is channel vec.
+    pub fn update_audio_for_data_channel_vec_with_options(
+        &mut self,
+        wave_format: &WaveFormat,
+        data_channel_vec: &Vec<Vec<f64>>,
+        options: &EncodeOptions,
+    ) -> Result<()> {
+        let (format_buf, bytes_data_vec) = if is_adpcm_format(wave_format.id) {
+            let adpcm_format = AdpcmFormat::new(wave_format, options.op_block_align)?;
+            let pcm_format = Self::adpcm_source_format(wave_format);
+            let pcm_bytes_data_vec = Self::from_data_channel_vec_with_options(&pcm_format, data_channel_vec, options)?;
+            Self::encode_adpcm_audio(&adpcm_format, &pcm_bytes_data_vec)
+        } else {
+            let bytes_data_vec = Self::from_data_channel_vec_with_options(wave_format, data_channel_vec, options)?;
+            (self.set_format(wave_format)?, bytes_data_vec)
+        };
+        self.update_audio_and_fit_chunks(wave_format.id, format_buf, bytes_data_vec, data_channel_vec.len())
+    }
+
+    /// ADPCM is encoded from 16bit PCM.
+    fn adpcm_source_format(wave_format: &WaveFormat) -> WaveFormat {
+        WaveFormat {
+            id: WAVEFORMAT_ID_PCM,
+            bits: 16,
+            ..*wave_format
+        }
+    }
+
+    /// Encode 16bit PCM to ADPCM blocks.
+    /// Return Value: (`Vec<u8>`, `Vec<u8>`): "fmt" chunk body and "data" chunk body.
+    fn encode_adpcm_audio(adpcm_format: &AdpcmFormat, pcm_bytes_data_vec: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let channel = adpcm_format.wave_format.channel;
+        let mut channel_pcm_vec: Vec<Vec<i16>> = vec![Vec::new(); channel];
+        for (sample_idx, bytes) in pcm_bytes_data_vec.chunks_exact(2).enumerate() {
+            channel_pcm_vec[sample_idx % channel].push(i16::from_le_bytes([bytes[0], bytes[1]]));
+        }
+        (adpcm_format.to_format_chunk(), adpcm_format.encode(&channel_pcm_vec))
+    }
+
+    /// Update "fmt" and "data" chunk. "fact" chunk has the number of samples of the new audio data, and cue points are kept within it.
+    /// "fact" chunk and the cue points are fitted before any chunk is changed, so that an error does not leave the chunks half updated.
+    fn update_audio_and_fit_chunks(
+        &mut self,
+        format_id: usize,
+        format_buf: Vec<u8>,
+        bytes_data_vec: Vec<u8>,
+        frame_len: usize,
+    ) -> Result<()> {
+        let op_fact_chunk = self.make_fitted_fact_chunk(format_id, frame_len)?;
+        let op_cue_chunk_bodies = self.make_fitted_cue_chunk_bodies(frame_len);
+        self.update_audio(format_buf, bytes_data_vec)?;
+        if let Some(fact_chunk) = op_fact_chunk {
+            self.update_fact(&fact_chunk)?;
+        }
+        if let Some(cue_chunk_bodies) = op_cue_chunk_bodies {
+            self.set_cue_chunk_bodies(cue_chunk_bodies)?;
+        }
+        Ok(())
+    }
+
+    fn from_channel_data_vec(wave_format: &WaveFormat, channel_data_vec: &Vec<Vec<f64>>) -> Result<Vec<u8>> {
+        Self::from_channel_data_vec_with_options(wave_format, channel_data_vec, &EncodeOptions::default())
+    }
+
+    fn from_channel_data_vec_with_options(
+        wave_format: &WaveFormat,
+        channel_data_vec: &Vec<Vec<f64>>,
+        options: &EncodeOptions,
+    ) -> Result<Vec<u8>> {
         check_channel_data_vec_len(channel_data_vec)?;
-        let format_buf: Vec<u8> = self.set_format(wave_format)?;
+        if channel_data_vec.len() != wave_format.channel {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::AudioDataVecLengthError,
+                Some("channel length is different from wave format".to_string()),
+            ));
+        }
+        let mut quantizer = Quantizer::new(options, wave_format.channel);
         let mut bytes_data_vec: Vec<u8> = Vec::new();
         for (data_idx, _) in channel_data_vec[0].iter().enumerate() {
             for channel_idx in 0..wave_format.channel {
-                bytes_data_vec.append(&mut f64wave_to_bytes(
+                bytes_data_vec.append(&mut quantizer.f64wave_to_bytes(
                     wave_format.id,
                     channel_data_vec[channel_idx][data_idx],
                     wave_format.bits,
+                    channel_idx,
                 )?);
             }
         }
-        self.update_audio(format_buf, bytes_data_vec)?;
-        Ok(())
+        Ok(bytes_data_vec)
     }
 
-    /// Update audio data (update "fmt" and "data" chunk). If "fmt" or "data" argument chunk do not exist, those chunks are added.
-    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is data vec. Inner is channel vec.
-    pub fn update_audio_for_data_channel_vec(
-        &mut self,
+    fn from_data_channel_vec(wave_format: &WaveFormat, data_channel_vec: &Vec<Vec<f64>>) -> Result<Vec<u8>> {
+        Self::from_data_channel_vec_with_options(wave_format, data_channel_vec, &EncodeOptions::default())
+    }
+
+    fn from_data_channel_vec_with_options(
         wave_format: &WaveFormat,
         data_channel_vec: &Vec<Vec<f64>>,
-    ) -> Result<()> {
+        options: &EncodeOptions,
+    ) -> Result<Vec<u8>> {
         check_data_channel_vec_len(data_channel_vec)?;
-        let format_buf: Vec<u8> = self.set_format(wave_format)?;
+        if data_channel_vec[0].len() != wave_format.channel {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::AudioDataVecLengthError,
+                Some("channel length is different from wave format".to_string()),
+            ));
+        }
+        let mut quantizer = Quantizer::new(options, wave_format.channel);
         let mut bytes_data_vec: Vec<u8> = Vec::new();
         for (data_idx, _) in data_channel_vec.iter().enumerate() {
             for channel_idx in 0..wave_format.channel {
-                bytes_data_vec.append(&mut f64wave_to_bytes(
+                bytes_data_vec.append(&mut quantizer.f64wave_to_bytes(
                     wave_format.id,
                     data_channel_vec[data_idx][channel_idx],
                     wave_format.bits,
+                    channel_idx,
                 )?);
             }
         }
-        self.update_audio(format_buf, bytes_data_vec)?;
-        Ok(())
+        Ok(bytes_data_vec)
     }
 
     fn update_audio(&mut self, format_buf: Vec<u8>, bytes_data_vec: Vec<u8>) -> Result<()> {
@@ -558,7 +1301,7 @@ impl WavFile {
     ) -> Result<()> {
         let mut total_size: usize = 0;
         if op_chunk_idx.is_none() {
-            total_size = new_sub_chunk_body_size;
+            total_size = new_sub_chunk_body_size + new_sub_chunk_body_size % 2;
         }
 
         for (idx, sub_chunk) in self.sub_chunks.iter().enumerate() {
@@ -572,12 +1315,14 @@ impl WavFile {
             } else {
                 sub_chunk_body_size = sub_chunk.bytes_data_vec.len();
             }
-            // 8 = chunk_id + body_size
-            if let Some(sub_chunk_size) = sub_chunk_body_size.checked_add(8) {
+            // 8 = chunk_id + body_size, odd-length body is followed by a pad byte.
+            if let Some(sub_chunk_size) = sub_chunk_body_size.checked_add(8 + sub_chunk_body_size % 2) {
                 if let Some(new_total_size) = total_size.checked_add(sub_chunk_size) {
                     total_size = new_total_size;
                     // 12 = "RIFF" + RIFF Size + "WAVE"
-                    if total_size > 0xffffffff - 12 {
+                    if (self.container == WavContainer::Riff || self.container == WavContainer::Rifx)
+                        && total_size > 0xffffffff - 12
+                    {
                         return Err(WavF64VecError::new(
                             WavF64VecErrorKind::SubChunkSizeTooLarge,
                             Some(chunk_id_string),
@@ -623,60 +1368,64 @@ impl WavFile {
         }
         None
     }
+
+    /// Get the index of the "LIST" chunk which has the list type. (e.g. "INFO", "adtl")
+    pub(crate) fn get_list_chunk_idx(&self, list_type: [u8; 4]) -> Option<usize> {
+        self.sub_chunks.iter().position(|sub_chunk| {
+            sub_chunk.chunk_id == [b'L', b'I', b'S', b'T'] && sub_chunk.bytes_data_vec.get(0..4) == Some(&list_type[..])
+        })
+    }
+
+    /// Update the "LIST" chunk which has the same list type as the chunk body. If it does not exist, the chunk is added.
+    pub(crate) fn update_list_chunk(&mut self, chunk_body: Vec<u8>) -> Result<()> {
+        let op_chunk_idx = self.get_list_chunk_idx(<[u8; 4]>::try_from(&chunk_body[0..4])?);
+        self.precheck_sub_chunk_size(op_chunk_idx, chunk_body.len(), "LIST".to_string())?;
+        if let Some(chunk_idx) = op_chunk_idx {
+            self.sub_chunks[chunk_idx].bytes_data_vec = chunk_body;
+        } else {
+            self.sub_chunks.push(SubChunk {
+                chunk_id: [b'L', b'I', b'S', b'T'],
+                bytes_data_vec: chunk_body,
+            });
+        }
+        Ok(())
+    }
 }
 
 /// Convert from a bytes data vector to a audio data value(`f64`).
 pub fn bytes_to_f64wave(format_id: usize, bytes: &[u8]) -> Result<f64> {
+    bytes_to_f64wave_with_scaling(format_id, bytes, &PcmScaling::default())
+}
+
+/// Convert from a bytes data vector to a audio data value(`f64`) with the scaling of integer PCM.
+pub fn bytes_to_f64wave_with_scaling(format_id: usize, bytes: &[u8], scaling: &PcmScaling) -> Result<f64> {
     let bytes_len = bytes.len();
 
     match format_id {
-        WAVEFORMAT_ID_PCM => {
-            if bytes_len == 1 {
-                let mut buffer: [u8; 1] = [0; 1];
-                //to signed 8bit
-                if bytes[0] < 128 {
-                    buffer[0] = bytes[0] + 128;
-                } else {
-                    buffer[0] = bytes[0] - 128;
-                }
-                Ok(f64::from(i8::from_le_bytes(buffer)) / f64::from(BIT8_WAVE_DIVISOR))
-            } else if bytes_len == 2 {
-                //signed 16bit
-                let mut buffer: [u8; 2] = [0; 2];
-                for i in 0..bytes_len {
-                    buffer[i] = bytes[i];
-                }
-                Ok(f64::from(i16::from_le_bytes(buffer)) / f64::from(BIT16_WAVE_DIVISOR))
-            } else if bytes_len == 3 {
-                //signed 24bit
-                let mut buffer: [u8; 4] = [0; 4];
-                for i in 0..bytes_len {
-                    buffer[i] = bytes[i];
-                }
-                let mut i32_val = i32::from_le_bytes(buffer);
-                if i32_val > 0x7fffffi32 {
-                    i32_val -= 0x1000000i32;
-                }
-                Ok(f64::from(i32_val) / f64::from(BIT24_WAVE_DIVISOR))
-            } else if bytes_len == 4 {
-                //signed 32bit
-                let mut buffer: [u8; 4] = [0; 4];
-                for i in 0..bytes_len {
-                    buffer[i] = bytes[i];
-                }
-                Ok(f64::from(i32::from_le_bytes(buffer)) / BIT32_WAVE_DIVISOR as f64)
-            } else {
-                Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None))
-            }
-        }
+        WAVEFORMAT_ID_PCM => Ok(f64::from(bytes_to_pcm(bytes)?) / scaling.full_scale(bytes_len * 8)?),
         WAVEFORMAT_ID_IEEE_FLOAT => {
             if bytes_len == 4 {
                 //32bit float
                 Ok(f64::from(f32::from_le_bytes(<[u8; 4]>::try_from(bytes)?)))
+            } else if bytes_len == 8 {
+                //64bit float
+                Ok(f64::from_le_bytes(<[u8; 8]>::try_from(bytes)?))
             } else {
                 Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None))
             }
         }
+        WAVEFORMAT_ID_ALAW | WAVEFORMAT_ID_MULAW => {
+            if bytes_len != 1 {
+                return Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None));
+            }
+            // A-law and mu-law are expanded to 16bit PCM.
+            let pcm_val = if format_id == WAVEFORMAT_ID_ALAW {
+                alaw_to_pcm(bytes[0])
+            } else {
+                mulaw_to_pcm(bytes[0])
+            };
+            Ok(f64::from(pcm_val) / scaling.full_scale(16)?)
+        }
         _ => Err(WavF64VecError::new(
             WavF64VecErrorKind::FormatIsNotSupported,
             Some("format id".to_string()),
@@ -686,64 +1435,15 @@ pub fn bytes_to_f64wave(format_id: usize, bytes: &[u8]) -> Result<f64> {
 
 /// Convert from a audio data value(`f64`) to a bytes data vector .
 pub fn f64wave_to_bytes(format_id: usize, f64_val: f64, bits: usize) -> Result<Vec<u8>> {
+    f64wave_to_bytes_with_scaling(format_id, f64_val, bits, &PcmScaling::default())
+}
+
+/// Convert from a audio data value(`f64`) to a bytes data vector with the scaling of integer PCM.
+/// Integer PCM values are rounded to nearest and clipped.
+pub fn f64wave_to_bytes_with_scaling(format_id: usize, f64_val: f64, bits: usize, scaling: &PcmScaling) -> Result<Vec<u8>> {
     let bytes_len = bits / 8;
     match format_id {
-        WAVEFORMAT_ID_PCM => {
-            // TODO: Max Value Check for each length
-            if bytes_len == 1 {
-                let mut tmp_val = (f64_val * f64::from(BIT8_WAVE_DIVISOR)).round();
-                if tmp_val < i8::MIN as f64 {
-                    tmp_val = i8::MIN as f64;
-                } else if tmp_val > i8::MAX as f64 {
-                    tmp_val = i8::MAX as f64;
-                }
-
-                let mut buffer: [u8; 1] = (tmp_val as i8).to_le_bytes();
-                //to unsigned 8bit
-                if buffer[0] < 128 {
-                    buffer[0] += 128;
-                } else {
-                    buffer[0] -= 128;
-                }
-                Ok(buffer.to_vec())
-            } else if bytes_len == 2 {
-                let mut tmp_val = (f64_val * f64::from(BIT16_WAVE_DIVISOR)).round();
-                if tmp_val < i16::MIN as f64 {
-                    tmp_val = i16::MIN as f64;
-                } else if tmp_val > i16::MAX as f64 {
-                    tmp_val = i16::MAX as f64;
-                }
-
-                let buffer: [u8; 2] = (tmp_val as i16).to_le_bytes();
-                //signed 16bit
-                Ok(buffer.to_vec())
-            } else if bytes_len == 3 {
-                // Calcurate in 32bit
-                let mut tmp_val = (f64_val * BIT32_WAVE_DIVISOR as f64).round();
-                if tmp_val < i32::MIN as f64 {
-                    tmp_val = i32::MIN as f64;
-                } else if tmp_val > i32::MAX as f64 {
-                    tmp_val = i32::MAX as f64;
-                }
-
-                let buffer: [u8; 4] = (tmp_val as i32).to_le_bytes();
-                //signed 24bit
-                Ok(buffer[1..4].to_vec())
-            } else if bytes_len == 4 {
-                let mut tmp_val = (f64_val * BIT32_WAVE_DIVISOR as f64).round();
-                if tmp_val < i32::MIN as f64 {
-                    tmp_val = i32::MIN as f64;
-                } else if tmp_val > i32::MAX as f64 {
-                    tmp_val = i32::MAX as f64;
-                }
-
-                let buffer: [u8; 4] = (tmp_val as i32).to_le_bytes();
-                //signed 32bit
-                Ok(buffer.to_vec())
-            } else {
-                Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None))
-            }
-        }
+        WAVEFORMAT_ID_PCM => pcm_to_bytes(f64_val * scaling.full_scale(bits)?, bytes_len),
         WAVEFORMAT_ID_IEEE_FLOAT => {
             if bytes_len == 4 {
                 let f32_val: f32;
@@ -757,10 +1457,28 @@ pub fn f64wave_to_bytes(format_id: usize, f64_val: f64, bits: usize) -> Result<V
                 let buffer: [u8; 4] = f32_val.to_le_bytes();
                 //32bit float
                 Ok(buffer.to_vec())
+            } else if bytes_len == 8 {
+                let buffer: [u8; 8] = f64_val.to_le_bytes();
+                //64bit float
+                Ok(buffer.to_vec())
             } else {
                 Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None))
             }
         }
+        WAVEFORMAT_ID_ALAW | WAVEFORMAT_ID_MULAW => {
+            if bytes_len != 1 {
+                return Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None));
+            }
+            // A-law and mu-law are compressed from 16bit PCM.
+            let pcm_val = (f64_val * scaling.full_scale(16)?)
+                .round()
+                .clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+            if format_id == WAVEFORMAT_ID_ALAW {
+                Ok(vec![pcm_to_alaw(pcm_val)])
+            } else {
+                Ok(vec![pcm_to_mulaw(pcm_val)])
+            }
+        }
         _ => Err(WavF64VecError::new(
             WavF64VecErrorKind::FormatIsNotSupported,
             Some("format id".to_string()),
@@ -869,7 +1587,7 @@ pub fn convert_sampling_rate_for_data_channel_vec(
 }
 
 fn check_channel_data_vec_len(channel_data_vec: &Vec<Vec<f64>>) -> Result<()> {
-    if channel_data_vec.len() == 0 || channel_data_vec.len() > 2 {
+    if channel_data_vec.is_empty() || channel_data_vec.len() > CHANNEL_MAX {
         return Err(WavF64VecError::new(
             WavF64VecErrorKind::AudioDataVecLengthError,
             Some("channel length".to_string()),
@@ -888,8 +1606,14 @@ fn check_channel_data_vec_len(channel_data_vec: &Vec<Vec<f64>>) -> Result<()> {
 }
 
 fn check_data_channel_vec_len(data_channel_vec: &Vec<Vec<f64>>) -> Result<()> {
+    if data_channel_vec.is_empty() {
+        return Err(WavF64VecError::new(
+            WavF64VecErrorKind::AudioDataVecLengthError,
+            Some("data length".to_string()),
+        ));
+    }
     let channel_len = data_channel_vec[0].len();
-    if channel_len == 0 || channel_len > 2 {
+    if channel_len == 0 || channel_len > CHANNEL_MAX {
         return Err(WavF64VecError::new(
             WavF64VecErrorKind::AudioDataVecLengthError,
             Some("channel length".to_string()),
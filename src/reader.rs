@@ -0,0 +1,535 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use crate::adpcm::*;
+use crate::error::*;
+use crate::repair::*;
+use crate::rf64::*;
+use crate::rifx::*;
+use crate::wave64::*;
+use crate::{DecodeOptions, SubChunk, WavContainer, WavFile, WaveFormat};
+
+/// Position of a sub chunk body in a stream.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct SubChunkHead {
+    /// Chunk identifier.
+    pub(crate) chunk_id: [u8; 4],
+    /// Stream position of the chunk head.
+    pub(crate) head_addr: u64,
+    /// GUID of the Wave64 chunk which has no RIFF chunk id. (`W64_GUID_CHUNK_ID`)
+    pub(crate) op_guid: Option<[u8; 16]>,
+    /// Stream position of the chunk body.
+    pub(crate) body_head: u64,
+    /// Chunk body size.
+    pub(crate) body_size: u64,
+}
+
+/// RIFF header of a stream.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct RiffHeader {
+    /// Container of the stream.
+    pub(crate) container: WavContainer,
+    /// Stream position of the end of the sub chunks.
+    pub(crate) chunks_end: u64,
+    /// "ds64" chunk of RF64 and BW64.
+    pub(crate) op_ds64: Option<Ds64>,
+    /// Whether RIFF size matches the stream length.
+    pub(crate) riff_size_is_valid: bool,
+}
+
+/// Read the RIFF header (and "ds64" chunk of RF64 and BW64, or the header of Wave64). The stream position moves to the head of the sub chunks.
+/// If `op_warnings` is `Some`, a wrong RIFF size is tolerated and reported to it.
+pub(crate) fn read_riff_header<R: Read + Seek>(
+    reader: &mut R,
+    op_warnings: Option<&mut Vec<WavRepairWarning>>,
+) -> Result<RiffHeader> {
+    let stream_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header_buf = [0u8; 12];
+    if stream_len < 12 {
+        return Err(WavF64VecError::new_at(
+            WavF64VecErrorKind::FileIsNotCompatibleFormat,
+            Some("\"RIFF\"".to_string()),
+            None,
+            0,
+        ));
+    }
+    reader.read_exact(&mut header_buf)?;
+    // "RIFF", "RIFX", "RF64", "BW64" or "riff" GUID
+    let container = match header_buf[0x00..0x04] {
+        [b'R', b'I', b'F', b'F'] => WavContainer::Riff,
+        [b'R', b'I', b'F', b'X'] => WavContainer::Rifx,
+        [b'R', b'F', b'6', b'4'] => WavContainer::Rf64,
+        [b'B', b'W', b'6', b'4'] => WavContainer::Bw64,
+        [b'r', b'i', b'f', b'f'] => return read_w64_header(reader, stream_len, op_warnings),
+        _ => {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::FileIsNotCompatibleFormat,
+                Some("\"RIFF\"".to_string()),
+                None,
+                0,
+            ));
+        }
+    };
+    // "WAVE"
+    if header_buf[0x08..0x0c] != [b'W', b'A', b'V', b'E'] {
+        return Err(WavF64VecError::new_at(
+            WavF64VecErrorKind::FileIsNotCompatibleFormat,
+            Some("\"WAVE\"".to_string()),
+            None,
+            0x08,
+        ));
+    }
+    // "ds64"
+    let mut op_ds64: Option<Ds64> = None;
+    let mut riff_size = if container == WavContainer::Rifx {
+        u64::from(u32::from_be_bytes(<[u8; 4]>::try_from(&header_buf[0x04..0x08])?))
+    } else {
+        u64::from(u32::from_le_bytes(<[u8; 4]>::try_from(&header_buf[0x04..0x08])?))
+    };
+    if container == WavContainer::Rf64 || container == WavContainer::Bw64 {
+        let mut chunk_head_buf = [0u8; 8];
+        if stream_len - 12 < 8 {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::NoRequiredSubChunk,
+                Some("\"ds64\"".to_string()),
+                Some([b'd', b's', b'6', b'4']),
+                0x0c,
+            ));
+        }
+        reader.read_exact(&mut chunk_head_buf)?;
+        if chunk_head_buf[0x00..0x04] != [b'd', b's', b'6', b'4'] {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::NoRequiredSubChunk,
+                Some("\"ds64\"".to_string()),
+                Some([b'd', b's', b'6', b'4']),
+                0x0c,
+            ));
+        }
+        let chunk_body_size = u64::from(u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_head_buf[0x04..0x08])?));
+        if stream_len - 12 - 8 < chunk_body_size {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"ds64\"".to_string()),
+                Some([b'd', b's', b'6', b'4']),
+                0x0c,
+            ));
+        }
+        let mut chunk_body = vec![0u8; usize::try_from(chunk_body_size)?];
+        reader.read_exact(&mut chunk_body)?;
+        let ds64 = Ds64::from_chunk(&chunk_body).map_err(|err| err.at([b'd', b's', b'6', b'4'], Some(0x0c)))?;
+        riff_size = ds64.riff_size;
+        op_ds64 = Some(ds64);
+    }
+    // RIFF Size
+    let riff_size_is_valid = riff_size_check(riff_size, stream_len - 8, 0x04, op_warnings)?;
+    Ok(RiffHeader {
+        container,
+        chunks_end: stream_len,
+        op_ds64,
+        riff_size_is_valid,
+    })
+}
+
+/// Read the header of Wave64. The stream position moves to the head of the sub chunks.
+fn read_w64_header<R: Read + Seek>(
+    reader: &mut R,
+    stream_len: u64,
+    op_warnings: Option<&mut Vec<WavRepairWarning>>,
+) -> Result<RiffHeader> {
+    let mut header_buf = [0u8; W64_HEADER_SIZE as usize];
+    if stream_len < W64_HEADER_SIZE {
+        return Err(WavF64VecError::new_at(
+            WavF64VecErrorKind::FileIsNotCompatibleFormat,
+            Some("\"riff\"".to_string()),
+            None,
+            0,
+        ));
+    }
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_exact(&mut header_buf)?;
+    // "riff" GUID
+    if header_buf[0x00..0x10] != W64_RIFF_GUID {
+        return Err(WavF64VecError::new_at(
+            WavF64VecErrorKind::FileIsNotCompatibleFormat,
+            Some("\"riff\"".to_string()),
+            None,
+            0,
+        ));
+    }
+    // "wave" GUID
+    if header_buf[0x18..0x28] != W64_WAVE_GUID {
+        return Err(WavF64VecError::new_at(
+            WavF64VecErrorKind::FileIsNotCompatibleFormat,
+            Some("\"wave\"".to_string()),
+            None,
+            0x18,
+        ));
+    }
+    // File Size (including the header)
+    let riff_size = u64::from_le_bytes(<[u8; 8]>::try_from(&header_buf[0x10..0x18])?);
+    let riff_size_is_valid = riff_size_check(riff_size, stream_len, 0x10, op_warnings)?;
+    Ok(RiffHeader {
+        container: WavContainer::Wave64,
+        chunks_end: stream_len,
+        op_ds64: None,
+        riff_size_is_valid,
+    })
+}
+
+/// Check RIFF size matches the stream length. If `op_warnings` is `Some`, the mismatch is reported to it.
+fn riff_size_check(
+    riff_size: u64,
+    actual_size: u64,
+    riff_size_pos: u64,
+    op_warnings: Option<&mut Vec<WavRepairWarning>>,
+) -> Result<bool> {
+    if riff_size == actual_size {
+        return Ok(true);
+    }
+    if let Some(warnings) = op_warnings {
+        warnings.push(WavRepairWarning::RiffSizeMismatch {
+            declared: riff_size,
+            actual: actual_size,
+        });
+        Ok(false)
+    } else {
+        Err(WavF64VecError::new_at(
+            WavF64VecErrorKind::FileIsNotCompatibleFormat,
+            Some("RIFF Size".to_string()),
+            None,
+            riff_size_pos,
+        ))
+    }
+}
+
+/// Walk the sub chunks by seeking from the current position to the end of the sub chunks. Chunk bodies are not read.
+/// If `op_warnings` is `Some`, placeholder sizes, truncated chunks and trailing bytes are tolerated and reported to it.
+pub(crate) fn read_sub_chunk_heads<R: Read + Seek>(
+    reader: &mut R,
+    riff_header: &RiffHeader,
+    mut op_warnings: Option<&mut Vec<WavRepairWarning>>,
+) -> Result<Vec<SubChunkHead>> {
+    let chunks_end = riff_header.chunks_end;
+    let is_w64 = riff_header.container == WavContainer::Wave64;
+    // 8 = chunk_id + body_size
+    let chunk_head_size = if is_w64 { W64_CHUNK_HEAD_SIZE } else { 8 };
+    let mut sub_chunk_heads: Vec<SubChunkHead> = Vec::new();
+    let mut chunk_head_addr = reader.stream_position()?;
+    while chunks_end - chunk_head_addr >= chunk_head_size {
+        let mut op_guid: Option<[u8; 16]> = None;
+        let (chunk_id, mut chunk_body_size) = if is_w64 {
+            let mut chunk_head_buf = [0u8; W64_CHUNK_HEAD_SIZE as usize];
+            reader.read_exact(&mut chunk_head_buf)?;
+            // Chunk size includes the chunk head.
+            let chunk_size = u64::from_le_bytes(<[u8; 8]>::try_from(&chunk_head_buf[0x10..0x18])?);
+            let guid = <[u8; 16]>::try_from(&chunk_head_buf[0x00..0x10])?;
+            match guid_to_chunk_id(&guid) {
+                Some(chunk_id) => (chunk_id, chunk_size.saturating_sub(W64_CHUNK_HEAD_SIZE)),
+                // GUID without the RIFF equivalent is kept with the GUID.
+                None => {
+                    op_guid = Some(guid);
+                    (W64_GUID_CHUNK_ID, chunk_size.saturating_sub(W64_CHUNK_HEAD_SIZE))
+                }
+            }
+        } else {
+            let mut chunk_head_buf = [0u8; 8];
+            reader.read_exact(&mut chunk_head_buf)?;
+            let chunk_id = <[u8; 4]>::try_from(&chunk_head_buf[0x00..0x04])?;
+            let size_bytes = <[u8; 4]>::try_from(&chunk_head_buf[0x04..0x08])?;
+            if riff_header.container == WavContainer::Rifx {
+                (chunk_id, u64::from(u32::from_be_bytes(size_bytes)))
+            } else {
+                (chunk_id, u64::from(u32::from_le_bytes(size_bytes)))
+            }
+        };
+        let mut size_is_resolved = false;
+        if let Some(ds64) = &riff_header.op_ds64 {
+            if chunk_body_size == u64::from(RF64_SIZE_PLACEHOLDER) {
+                if let Some(ds64_chunk_size) = ds64.chunk_size(chunk_id) {
+                    chunk_body_size = ds64_chunk_size;
+                    size_is_resolved = true;
+                }
+            }
+        }
+        let remaining_size = chunks_end - chunk_head_addr - chunk_head_size;
+        if let Some(warnings) = op_warnings.as_deref_mut() {
+            if !chunk_id.iter().all(|byte| (0x20..=0x7e).contains(byte)) {
+                warnings.push(WavRepairWarning::TrailingBytes {
+                    offset: chunk_head_addr,
+                    size: chunks_end - chunk_head_addr,
+                });
+                return Ok(sub_chunk_heads);
+            }
+            // A recorder which stopped before fixing the sizes leaves 0 or 0xFFFFFFFF in "data" chunk size.
+            let is_placeholder = !size_is_resolved
+                && chunk_id == [b'd', b'a', b't', b'a']
+                && (chunk_body_size == u64::from(RF64_SIZE_PLACEHOLDER)
+                    || (chunk_body_size == 0 && !riff_header.riff_size_is_valid));
+            if is_placeholder && chunk_body_size != remaining_size {
+                warnings.push(WavRepairWarning::ChunkSizePlaceholder {
+                    chunk_id,
+                    declared: chunk_body_size,
+                    actual: remaining_size,
+                });
+                chunk_body_size = remaining_size;
+            } else if remaining_size < chunk_body_size {
+                warnings.push(WavRepairWarning::TruncatedChunk {
+                    chunk_id,
+                    declared: chunk_body_size,
+                    actual: remaining_size,
+                });
+                chunk_body_size = remaining_size;
+            }
+        }
+        if remaining_size < chunk_body_size {
+            return Err(WavF64VecError::new_at(
+                WavF64VecErrorKind::SubChunkSizeError,
+                None,
+                Some(chunk_id),
+                chunk_head_addr,
+            ));
+        }
+        sub_chunk_heads.push(SubChunkHead {
+            chunk_id,
+            head_addr: chunk_head_addr,
+            op_guid,
+            body_head: chunk_head_addr + chunk_head_size,
+            body_size: chunk_body_size,
+        });
+        chunk_head_addr += chunk_head_size + chunk_body_size;
+        // Wave64 chunk is aligned to 8 bytes. RIFF odd-length body is followed by a pad byte.
+        // The padding may be missing at the end of the stream.
+        let padding_size = if is_w64 {
+            w64_padding_size(chunk_body_size)
+        } else {
+            chunk_body_size % 2
+        };
+        chunk_head_addr = (chunk_head_addr + padding_size).min(chunks_end);
+        reader.seek(SeekFrom::Start(chunk_head_addr))?;
+    }
+    if chunk_head_addr < chunks_end {
+        if let Some(warnings) = op_warnings {
+            warnings.push(WavRepairWarning::TrailingBytes {
+                offset: chunk_head_addr,
+                size: chunks_end - chunk_head_addr,
+            });
+        }
+    }
+    Ok(sub_chunk_heads)
+}
+
+/// Streaming wav file reader.
+/// The sub chunks except "data" chunk are read when the reader is created, and audio data is read block by block.
+pub struct WavReader<R: Read + Seek> {
+    reader: R,
+    container: WavContainer,
+    wave_format: WaveFormat,
+    sub_chunks: Vec<SubChunk>,
+    data_body_head: u64,
+    frame_len: u64,
+    frame_pos: u64,
+}
+
+impl WavReader<BufReader<File>> {
+    /// Open wav file and Create structure with the file header.
+    pub fn open(file_path: &Path) -> Result<WavReader<BufReader<File>>> {
+        // -- Check Parameter --
+        if !file_path.is_file() {
+            return Err(WavF64VecError::new(WavF64VecErrorKind::PathIsNotFile, None));
+        }
+        WavReader::new(BufReader::new(File::open(file_path)?))
+    }
+}
+
+impl<R: Read + Seek> WavReader<R> {
+    /// Create structure with the header of the stream.
+    pub fn new(mut reader: R) -> Result<WavReader<R>> {
+        let riff_header = read_riff_header(&mut reader, None)?;
+        let sub_chunk_heads = read_sub_chunk_heads(&mut reader, &riff_header, None)?;
+
+        let mut sub_chunks: Vec<SubChunk> = Vec::new();
+        let mut op_wave_format: Option<WaveFormat> = None;
+        let mut op_format_chunk_addr: Option<u64> = None;
+        let mut op_data_chunk_head: Option<SubChunkHead> = None;
+        for sub_chunk_head in sub_chunk_heads {
+            if sub_chunk_head.chunk_id == [b'd', b'a', b't', b'a'] {
+                if op_data_chunk_head.is_some() {
+                    return Err(WavF64VecError::new_at(
+                        WavF64VecErrorKind::SubChunkDuplication,
+                        Some("\"data\"".to_string()),
+                        Some(sub_chunk_head.chunk_id),
+                        sub_chunk_head.head_addr,
+                    ));
+                }
+                op_data_chunk_head = Some(sub_chunk_head);
+                continue;
+            }
+            let mut bytes_data_vec = vec![0u8; usize::try_from(sub_chunk_head.body_size)?];
+            reader.seek(SeekFrom::Start(sub_chunk_head.body_head))?;
+            reader.read_exact(&mut bytes_data_vec)?;
+            if let Some(guid) = sub_chunk_head.op_guid {
+                bytes_data_vec.splice(0..0, guid);
+            }
+            if riff_header.container == WavContainer::Rifx {
+                if let Some(swapped_body) = swap_chunk_body(sub_chunk_head.chunk_id, &bytes_data_vec, true) {
+                    bytes_data_vec = swapped_body;
+                }
+            }
+            if sub_chunk_head.chunk_id == [b'f', b'm', b't', b' '] {
+                if op_wave_format.is_some() {
+                    return Err(WavF64VecError::new_at(
+                        WavF64VecErrorKind::SubChunkDuplication,
+                        Some("\"fmt\"".to_string()),
+                        Some(sub_chunk_head.chunk_id),
+                        sub_chunk_head.head_addr,
+                    ));
+                }
+                op_wave_format = Some(
+                    WavFile::get_format_from_chunk(&bytes_data_vec)
+                        .map_err(|err| err.at(sub_chunk_head.chunk_id, Some(sub_chunk_head.head_addr)))?,
+                );
+                op_format_chunk_addr = Some(sub_chunk_head.head_addr);
+            }
+            sub_chunks.push(SubChunk {
+                chunk_id: sub_chunk_head.chunk_id,
+                bytes_data_vec,
+            });
+        }
+
+        let wave_format = match op_wave_format {
+            Some(wave_format) => wave_format,
+            None => {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::NoRequiredSubChunk,
+                    Some("\"fmt\"".to_string()),
+                ));
+            }
+        };
+        let data_chunk_head = match op_data_chunk_head {
+            Some(data_chunk_head) => data_chunk_head,
+            None => {
+                return Err(WavF64VecError::new(
+                    WavF64VecErrorKind::NoRequiredSubChunk,
+                    Some("\"data\"".to_string()),
+                ));
+            }
+        };
+        WaveFormat::format_check(&wave_format).map_err(|err| err.at([b'f', b'm', b't', b' '], op_format_chunk_addr))?;
+        // ADPCM blocks can not be read frame by frame.
+        if is_adpcm_format(wave_format.id) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("format id".to_string()),
+            ));
+        }
+        if Self::block_size(&wave_format) == 0 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("block size".to_string()),
+            ));
+        }
+        reader.seek(SeekFrom::Start(data_chunk_head.body_head))?;
+
+        Ok(WavReader {
+            reader,
+            container: riff_header.container,
+            wave_format,
+            sub_chunks,
+            data_body_head: data_chunk_head.body_head,
+            frame_len: data_chunk_head.body_size / Self::block_size(&wave_format),
+            frame_pos: 0,
+        })
+    }
+
+    /// Get WaveFormat
+    pub fn get_format(&self) -> WaveFormat {
+        self.wave_format
+    }
+
+    /// Get the container of the stream.
+    pub fn get_container(&self) -> WavContainer {
+        self.container
+    }
+
+    /// Get the sub chunks except "data" chunk.
+    pub fn get_sub_chunks(&self) -> &Vec<SubChunk> {
+        &self.sub_chunks
+    }
+
+    /// Get the number of frames (samples per channel) of the audio data.
+    pub fn get_frame_len(&self) -> u64 {
+        self.frame_len
+    }
+
+    /// Get the frame position of the next read.
+    pub fn get_frame_pos(&self) -> u64 {
+        self.frame_pos
+    }
+
+    /// Move the frame position of the next read. The position is limited to the number of frames.
+    pub fn seek_frame(&mut self, frame_pos: u64) -> Result<()> {
+        self.frame_pos = frame_pos.min(self.frame_len);
+        self.reader.seek(SeekFrom::Start(
+            self.data_body_head + self.frame_pos * Self::block_size(&self.wave_format),
+        ))?;
+        Ok(())
+    }
+
+    /// Read audio data up to `frame_len` frames. At the end of audio data, inner vectors are shorter than `frame_len` or empty.
+    /// Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
+    pub fn read_audio_for_channel_data_vec(&mut self, frame_len: usize) -> Result<Vec<Vec<f64>>> {
+        self.read_audio_for_channel_data_vec_with_options(frame_len, &DecodeOptions::default())
+    }
+
+    /// Read audio data up to `frame_len` frames with the scaling of the options.
+    /// Return Value: `Vec<Vec<f64>>`: Outer is channel vec. Inner is data vec.
+    pub fn read_audio_for_channel_data_vec_with_options(
+        &mut self,
+        frame_len: usize,
+        options: &DecodeOptions,
+    ) -> Result<Vec<Vec<f64>>> {
+        let bytes_data_vec = self.read_bytes_audio(frame_len)?;
+        WavFile::to_channel_data_vec(&self.wave_format, bytes_data_vec, options)
+    }
+
+    /// Read audio data up to `frame_len` frames. At the end of audio data, outer vector is shorter than `frame_len` or empty.
+    /// Return Value: `Vec<Vec<f64>>`: Outer is data vec. Inner is channel vec.
+    pub fn read_audio_for_data_channel_vec(&mut self, frame_len: usize) -> Result<Vec<Vec<f64>>> {
+        self.read_audio_for_data_channel_vec_with_options(frame_len, &DecodeOptions::default())
+    }
+
+    /// Read audio data up to `frame_len` frames with the scaling of the options.
+    /// Return Value: `Vec<Vec<f64>>`: Outer is data vec. Inner is channel vec.
+    pub fn read_audio_for_data_channel_vec_with_options(
+        &mut self,
+        frame_len: usize,
+        options: &DecodeOptions,
+    ) -> Result<Vec<Vec<f64>>> {
+        let bytes_data_vec = self.read_bytes_audio(frame_len)?;
+        WavFile::to_data_channel_vec(&self.wave_format, bytes_data_vec, options)
+    }
+
+    /// Unwrap the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_bytes_audio(&mut self, frame_len: usize) -> Result<Vec<u8>> {
+        let read_frame_len = (self.frame_len - self.frame_pos).min(u64::try_from(frame_len)?);
+        let mut bytes_data_vec = vec![0u8; usize::try_from(read_frame_len * Self::block_size(&self.wave_format))?];
+        self.reader.read_exact(&mut bytes_data_vec)?;
+        if self.container == WavContainer::Rifx {
+            swap_sample_bytes(&self.wave_format, &mut bytes_data_vec)?;
+        }
+        self.frame_pos += read_frame_len;
+        Ok(bytes_data_vec)
+    }
+
+    fn block_size(wave_format: &WaveFormat) -> u64 {
+        (wave_format.channel * wave_format.bits / 8) as u64
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+
+/// What was repaired when a wav file was opened in the lenient mode.
+#[derive(Clone, PartialEq, Debug)]
+pub enum WavRepairWarning {
+    /// RIFF size does not match the stream length. The sub chunks are read until the end of the stream.
+    RiffSizeMismatch { declared: u64, actual: u64 },
+    /// Chunk size is a placeholder (`0` or `0xFFFFFFFF`). The chunk is extended to the end of the stream.
+    ChunkSizePlaceholder { chunk_id: [u8; 4], declared: u64, actual: u64 },
+    /// Chunk is shorter than declared. The chunk is cut at the end of the stream.
+    TruncatedChunk { chunk_id: [u8; 4], declared: u64, actual: u64 },
+    /// Bytes which are not a sub chunk at the end of the stream are ignored.
+    TrailingBytes { offset: u64, size: u64 },
+    /// Incomplete block at the end of the "data" chunk is removed.
+    PartialBlock { size: u64 },
+}
+
+impl fmt::Display for WavRepairWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> std::result::Result<(), fmt::Error> {
+        match self {
+            WavRepairWarning::RiffSizeMismatch { declared, actual } => {
+                write!(f, "RIFF size is {} but the sub chunks are {} bytes.", declared, actual)
+            }
+            WavRepairWarning::ChunkSizePlaceholder {
+                chunk_id,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "\"{}\" chunk size is placeholder {:#x}. It is extended to {} bytes.",
+                String::from_utf8_lossy(chunk_id),
+                declared,
+                actual
+            ),
+            WavRepairWarning::TruncatedChunk {
+                chunk_id,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "\"{}\" chunk size is {} but only {} bytes remain.",
+                String::from_utf8_lossy(chunk_id),
+                declared,
+                actual
+            ),
+            WavRepairWarning::TrailingBytes { offset, size } => {
+                write!(f, "{} bytes at offset {} are not a sub chunk.", size, offset)
+            }
+            WavRepairWarning::PartialBlock { size } => {
+                write!(
+                    f,
+                    "{} bytes of incomplete block at the end of \"data\" chunk are removed.",
+                    size
+                )
+            }
+        }
+    }
+}
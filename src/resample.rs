@@ -0,0 +1,376 @@
+use std::f64::consts::PI;
+
+use crate::error::*;
+use crate::{check_channel_data_vec_len, check_data_channel_vec_len, WaveFormat, CHANNEL_MAX};
+
+/// Maximum number of coefficients of the precomputed polyphase table.
+/// If the table is larger than this (the sampling rates have a small common divisor), coefficients are computed on demand.
+const POLYPHASE_TABLE_LEN_MAX: usize = 1 << 20;
+
+/// Quality preset of the band-limited resampler.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ResampleQuality {
+    /// Passband 80%, stopband attenuation 60dB.
+    Fast,
+    /// Passband 90%, stopband attenuation 100dB.
+    Medium,
+    /// Passband 95%, stopband attenuation 140dB.
+    Best,
+}
+
+/// Parameters of the band-limited resampler.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ResampleOptions {
+    /// Passband edge as a ratio of the Nyquist frequency of the lower sampling rate. (0.0 < passband < 1.0)
+    pub passband: f64,
+    /// Stopband attenuation (dB). The stopband starts at the Nyquist frequency of the lower sampling rate.
+    pub stopband_attenuation: f64,
+}
+
+impl ResampleOptions {
+    /// Create structure with the quality preset.
+    pub fn new(quality: ResampleQuality) -> ResampleOptions {
+        match quality {
+            ResampleQuality::Fast => ResampleOptions {
+                passband: 0.80,
+                stopband_attenuation: 60.0,
+            },
+            ResampleQuality::Medium => ResampleOptions {
+                passband: 0.90,
+                stopband_attenuation: 100.0,
+            },
+            ResampleQuality::Best => ResampleOptions {
+                passband: 0.95,
+                stopband_attenuation: 140.0,
+            },
+        }
+    }
+
+    /// Check the options are in range.
+    pub fn options_check(options: &ResampleOptions) -> Result<()> {
+        if !(options.passband > 0.0 && options.passband < 1.0) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::ParameterIsOutOfRange,
+                Some("passband".to_string()),
+            ));
+        }
+        if !(options.stopband_attenuation > 0.0 && options.stopband_attenuation <= 200.0) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::ParameterIsOutOfRange,
+                Some("stopband attenuation".to_string()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Kaiser windowed sinc lowpass filter evaluated at the fractional positions of `src / dst` sampling rate ratio.
+/// The filter is centered on each output sample, so the output has no delay.
+struct SincFilter {
+    /// Interpolation factor. (dst sampling rate / gcd)
+    up: u64,
+    /// Decimation factor. (src sampling rate / gcd)
+    down: u64,
+    /// Cutoff frequency as a ratio of the Nyquist frequency of the source.
+    cutoff: f64,
+    /// Half width of the window in source samples.
+    half_width: f64,
+    /// Kaiser window parameter.
+    beta: f64,
+    /// Number of source samples on each side of an output sample.
+    half_taps: usize,
+    /// Coefficients of each phase. (phase * 2 * half_taps + tap_idx)
+    op_table: Option<Vec<f64>>,
+}
+
+impl SincFilter {
+    fn new(src_sampling_rate: usize, dst_sampling_rate: usize, options: &ResampleOptions) -> SincFilter {
+        let divisor = gcd(src_sampling_rate, dst_sampling_rate);
+        let up = (dst_sampling_rate / divisor) as u64;
+        let down = (src_sampling_rate / divisor) as u64;
+        // Frequencies are normalized by the Nyquist frequency of the source.
+        let scale = (up as f64 / down as f64).min(1.0);
+        let cutoff = scale * (1.0 + options.passband) / 2.0;
+        let transition_width = scale * (1.0 - options.passband);
+        let attenuation = options.stopband_attenuation;
+        let beta = if attenuation > 50.0 {
+            0.1102 * (attenuation - 8.7)
+        } else if attenuation > 21.0 {
+            0.5842 * (attenuation - 21.0).powf(0.4) + 0.07886 * (attenuation - 21.0)
+        } else {
+            0.0
+        };
+        let taps_len = ((attenuation - 7.95) / (2.285 * PI * transition_width)).max(1.0);
+        let half_width = taps_len / 2.0;
+        let half_taps = half_width.ceil() as usize + 1;
+
+        let mut filter = SincFilter {
+            up,
+            down,
+            cutoff,
+            half_width,
+            beta,
+            half_taps,
+            op_table: None,
+        };
+        if (up as usize).saturating_mul(2 * half_taps) <= POLYPHASE_TABLE_LEN_MAX {
+            let mut table: Vec<f64> = Vec::with_capacity(up as usize * 2 * half_taps);
+            for phase in 0..up as usize {
+                for tap_idx in 0..2 * half_taps {
+                    table.push(filter.compute_tap(phase, tap_idx));
+                }
+            }
+            filter.op_table = Some(table);
+        }
+        filter
+    }
+
+    /// Number of output samples for the number of source samples.
+    fn dst_len(&self, src_len: u64) -> u64 {
+        (src_len * self.up).div_ceil(self.down)
+    }
+
+    /// Source sample index just before the output sample, and the phase of the output sample.
+    fn position(&self, dst_idx: u64) -> (u64, usize) {
+        let src_pos = dst_idx * self.down;
+        (src_pos / self.up, (src_pos % self.up) as usize)
+    }
+
+    /// Coefficient for the source sample `src_idx + tap_idx + 1 - half_taps`, where `src_idx` is the first value of `position()`.
+    fn tap(&self, phase: usize, tap_idx: usize) -> f64 {
+        if let Some(table) = &self.op_table {
+            table[phase * 2 * self.half_taps + tap_idx]
+        } else {
+            self.compute_tap(phase, tap_idx)
+        }
+    }
+
+    /// Output sample from the source samples. `src_val` returns the source sample of the index, or `None` outside the signal (regarded as zero).
+    fn interpolate<F: Fn(u64) -> Option<f64>>(&self, dst_idx: u64, src_val: F) -> f64 {
+        let (src_idx, phase) = self.position(dst_idx);
+        let mut sum = 0.0;
+        for tap_idx in 0..2 * self.half_taps {
+            if let Some(idx) = (src_idx + tap_idx as u64 + 1).checked_sub(self.half_taps as u64) {
+                if let Some(val) = src_val(idx) {
+                    sum += val * self.tap(phase, tap_idx);
+                }
+            }
+        }
+        sum
+    }
+
+    fn compute_tap(&self, phase: usize, tap_idx: usize) -> f64 {
+        // Distance from the output sample to the source sample.
+        let x = phase as f64 / self.up as f64 + self.half_taps as f64 - 1.0 - tap_idx as f64;
+        if x.abs() >= self.half_width {
+            return 0.0;
+        }
+        let sinc = if x == 0.0 {
+            1.0
+        } else {
+            (PI * self.cutoff * x).sin() / (PI * self.cutoff * x)
+        };
+        let ratio = x / self.half_width;
+        let window = bessel_i0(self.beta * (1.0 - ratio * ratio).sqrt()) / bessel_i0(self.beta);
+        self.cutoff * sinc * window
+    }
+}
+
+/// Streaming band-limited resampler. Audio data can be converted block by block of any size.
+/// The filter history and the fractional phase are carried between blocks, so the concatenated output is identical to
+/// `resample_for_channel_data_vec()` of the whole signal.
+pub struct Resampler {
+    op_filter: Option<SincFilter>,
+    /// Source samples which are still needed. Outer is channel vec. Inner is data vec.
+    history_channel_data_vec: Vec<Vec<f64>>,
+    /// Index of the first sample in the history.
+    history_head: u64,
+    /// Number of source samples passed so far.
+    src_len: u64,
+    /// Index of the next output sample.
+    dst_pos: u64,
+}
+
+impl Resampler {
+    /// Create structure.
+    pub fn new(
+        channel: usize,
+        src_sampling_rate: usize,
+        dst_sampling_rate: usize,
+        options: &ResampleOptions,
+    ) -> Result<Resampler> {
+        // -- Check Parameter --
+        if !(1..=CHANNEL_MAX).contains(&channel) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::AudioDataVecLengthError,
+                Some("channel length".to_string()),
+            ));
+        }
+        WaveFormat::sampling_rate_check(src_sampling_rate)?;
+        WaveFormat::sampling_rate_check(dst_sampling_rate)?;
+        ResampleOptions::options_check(options)?;
+
+        let op_filter = if src_sampling_rate == dst_sampling_rate {
+            None
+        } else {
+            Some(SincFilter::new(src_sampling_rate, dst_sampling_rate, options))
+        };
+        Ok(Resampler {
+            op_filter,
+            history_channel_data_vec: vec![Vec::new(); channel],
+            history_head: 0,
+            src_len: 0,
+            dst_pos: 0,
+        })
+    }
+
+    /// Convert a block of audio data. The output is delayed until the filter has enough following samples.
+    /// Parameters & return: Vec\<Vec\<f64\>\>: Outer is channel vec. Inner is data vec.
+    pub fn process_for_channel_data_vec(&mut self, src_channel_data_vec: &Vec<Vec<f64>>) -> Result<Vec<Vec<f64>>> {
+        check_channel_data_vec_len(src_channel_data_vec)?;
+        if src_channel_data_vec.len() != self.history_channel_data_vec.len() {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::AudioDataVecLengthError,
+                Some("channel length".to_string()),
+            ));
+        }
+        for (history_data_vec, src_data_vec) in self.history_channel_data_vec.iter_mut().zip(src_channel_data_vec) {
+            history_data_vec.extend_from_slice(src_data_vec);
+        }
+        self.src_len += src_channel_data_vec[0].len() as u64;
+        Ok(self.drain(false))
+    }
+
+    /// Convert a block of audio data. The output is delayed until the filter has enough following samples.
+    /// Parameters & return: Vec\<Vec\<f64\>\>: Outer is data vec. Inner is channel vec.
+    pub fn process_for_data_channel_vec(&mut self, src_data_channel_vec: &Vec<Vec<f64>>) -> Result<Vec<Vec<f64>>> {
+        if src_data_channel_vec.is_empty() {
+            return Ok(Vec::new());
+        }
+        check_data_channel_vec_len(src_data_channel_vec)?;
+        let dst_channel_data_vec =
+            self.process_for_channel_data_vec(&to_channel_data_vec(src_data_channel_vec, src_data_channel_vec[0].len()))?;
+        Ok(to_data_channel_vec(&dst_channel_data_vec))
+    }
+
+    /// Convert the rest of audio data. Samples after the end of the signal are regarded as zero.
+    /// Return: Vec\<Vec\<f64\>\>: Outer is channel vec. Inner is data vec.
+    pub fn finalize_for_channel_data_vec(mut self) -> Vec<Vec<f64>> {
+        self.drain(true)
+    }
+
+    /// Convert the rest of audio data. Samples after the end of the signal are regarded as zero.
+    /// Return: Vec\<Vec\<f64\>\>: Outer is data vec. Inner is channel vec.
+    pub fn finalize_for_data_channel_vec(mut self) -> Vec<Vec<f64>> {
+        to_data_channel_vec(&self.drain(true))
+    }
+
+    fn drain(&mut self, is_final: bool) -> Vec<Vec<f64>> {
+        let filter = match &self.op_filter {
+            Some(filter) => filter,
+            None => {
+                // Same sampling rate
+                self.history_head = self.src_len;
+                self.dst_pos = self.src_len;
+                return self.history_channel_data_vec.iter_mut().map(std::mem::take).collect();
+            }
+        };
+        let mut dst_channel_data_vec: Vec<Vec<f64>> = vec![Vec::new(); self.history_channel_data_vec.len()];
+        let dst_len = filter.dst_len(self.src_len);
+        while self.dst_pos < dst_len {
+            let (src_idx, _) = filter.position(self.dst_pos);
+            // The last tap is `src_idx + half_taps`.
+            if !is_final && src_idx + filter.half_taps as u64 >= self.src_len {
+                break;
+            }
+            for (channel_idx, history_data_vec) in self.history_channel_data_vec.iter().enumerate() {
+                let history_head = self.history_head;
+                dst_channel_data_vec[channel_idx].push(filter.interpolate(self.dst_pos, |idx| {
+                    history_data_vec
+                        .get(usize::try_from(idx.checked_sub(history_head)?).ok()?)
+                        .copied()
+                }));
+            }
+            self.dst_pos += 1;
+        }
+
+        // Drop the samples before the first tap of the next output sample.
+        let (src_idx, _) = filter.position(self.dst_pos);
+        let keep_head = (src_idx + 1).saturating_sub(filter.half_taps as u64).min(self.src_len);
+        if keep_head > self.history_head {
+            let drop_len = (keep_head - self.history_head) as usize;
+            for history_data_vec in &mut self.history_channel_data_vec {
+                history_data_vec.drain(..drop_len);
+            }
+            self.history_head = keep_head;
+        }
+        dst_channel_data_vec
+    }
+}
+
+/// Convert sampling rate with the band-limited (Kaiser windowed sinc) filter. Samples outside the signal are regarded as zero.
+/// Parameters & return: Vec\<Vec\<f64\>\>: Outer is channel vec. Inner is data vec.
+pub fn resample_for_channel_data_vec(
+    src_channel_data_vec: &Vec<Vec<f64>>,
+    src_sampling_rate: usize,
+    dst_sampling_rate: usize,
+    options: &ResampleOptions,
+) -> Result<Vec<Vec<f64>>> {
+    check_channel_data_vec_len(src_channel_data_vec)?;
+    let mut resampler = Resampler::new(src_channel_data_vec.len(), src_sampling_rate, dst_sampling_rate, options)?;
+    let mut dst_channel_data_vec = resampler.process_for_channel_data_vec(src_channel_data_vec)?;
+    for (dst_data_vec, mut rest_data_vec) in dst_channel_data_vec.iter_mut().zip(resampler.finalize_for_channel_data_vec()) {
+        dst_data_vec.append(&mut rest_data_vec);
+    }
+    Ok(dst_channel_data_vec)
+}
+
+/// Convert sampling rate with the band-limited (Kaiser windowed sinc) filter. Samples outside the signal are regarded as zero.
+/// Parameters & return: Vec\<Vec\<f64\>\>: Outer is data vec. Inner is channel vec.
+pub fn resample_for_data_channel_vec(
+    src_data_channel_vec: &Vec<Vec<f64>>,
+    src_sampling_rate: usize,
+    dst_sampling_rate: usize,
+    options: &ResampleOptions,
+) -> Result<Vec<Vec<f64>>> {
+    check_data_channel_vec_len(src_data_channel_vec)?;
+    let src_channel_data_vec = to_channel_data_vec(src_data_channel_vec, src_data_channel_vec[0].len());
+    let dst_channel_data_vec =
+        resample_for_channel_data_vec(&src_channel_data_vec, src_sampling_rate, dst_sampling_rate, options)?;
+    Ok(to_data_channel_vec(&dst_channel_data_vec))
+}
+
+fn to_channel_data_vec(data_channel_vec: &[Vec<f64>], channel_len: usize) -> Vec<Vec<f64>> {
+    (0..channel_len)
+        .map(|channel_idx| data_channel_vec.iter().map(|channel_vec| channel_vec[channel_idx]).collect())
+        .collect()
+}
+
+fn to_data_channel_vec(channel_data_vec: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    (0..channel_data_vec[0].len())
+        .map(|data_idx| channel_data_vec.iter().map(|data_vec| data_vec[data_idx]).collect())
+        .collect()
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Modified Bessel function of the first kind, order 0.
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..500 {
+        term *= half_x / k as f64;
+        let term_squared = term * term;
+        sum += term_squared;
+        if term_squared < sum * 1e-21 {
+            break;
+        }
+    }
+    sum
+}
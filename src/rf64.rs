@@ -0,0 +1,84 @@
+use crate::error::*;
+
+/// "ds64" chunk body size without the table.
+/// * 8 = RIFF size
+/// * 8 = "data" chunk size
+/// * 8 = sample count
+/// * 4 = table length
+pub(crate) const DS64_CHUNK_MIN_SIZE: usize = 28;
+
+/// Chunk size and RIFF size which mean "see the ds64 chunk".
+pub(crate) const RF64_SIZE_PLACEHOLDER: u32 = 0xffffffff;
+
+/// "ds64" chunk of RF64 and BW64. It has 64bit sizes instead of 32bit sizes of the RIFF header and sub chunks.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct Ds64 {
+    /// RIFF size.
+    pub(crate) riff_size: u64,
+    /// "data" chunk body size.
+    pub(crate) data_size: u64,
+    /// Number of samples per channel.
+    pub(crate) sample_count: u64,
+    /// Body sizes of the other chunks which are larger than 32bit.
+    pub(crate) table: Vec<([u8; 4], u64)>,
+}
+
+impl Ds64 {
+    pub(crate) fn from_chunk(chunk_body: &[u8]) -> Result<Ds64> {
+        if chunk_body.len() < DS64_CHUNK_MIN_SIZE {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"ds64\"".to_string()),
+            ));
+        }
+        let riff_size = u64::from_le_bytes(<[u8; 8]>::try_from(&chunk_body[0x00..0x08])?);
+        let data_size = u64::from_le_bytes(<[u8; 8]>::try_from(&chunk_body[0x08..0x10])?);
+        let sample_count = u64::from_le_bytes(<[u8; 8]>::try_from(&chunk_body[0x10..0x18])?);
+        let table_len = usize::try_from(u32::from_le_bytes(<[u8; 4]>::try_from(&chunk_body[0x18..0x1c])?))?;
+        // 12 = chunk_id + 64bit chunk size
+        if (chunk_body.len() - DS64_CHUNK_MIN_SIZE) / 12 < table_len {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"ds64\"".to_string()),
+            ));
+        }
+        let mut table: Vec<([u8; 4], u64)> = Vec::new();
+        for table_idx in 0..table_len {
+            let stt = DS64_CHUNK_MIN_SIZE + table_idx * 12;
+            table.push((
+                <[u8; 4]>::try_from(&chunk_body[stt..stt + 4])?,
+                u64::from_le_bytes(<[u8; 8]>::try_from(&chunk_body[stt + 4..stt + 12])?),
+            ));
+        }
+        Ok(Ds64 {
+            riff_size,
+            data_size,
+            sample_count,
+            table,
+        })
+    }
+
+    pub(crate) fn to_chunk(&self) -> Vec<u8> {
+        let mut chunk_body: Vec<u8> = Vec::new();
+        chunk_body.append(&mut self.riff_size.to_le_bytes().to_vec());
+        chunk_body.append(&mut self.data_size.to_le_bytes().to_vec());
+        chunk_body.append(&mut self.sample_count.to_le_bytes().to_vec());
+        chunk_body.append(&mut (self.table.len() as u32).to_le_bytes().to_vec());
+        for (chunk_id, chunk_size) in &self.table {
+            chunk_body.append(&mut chunk_id.to_vec());
+            chunk_body.append(&mut chunk_size.to_le_bytes().to_vec());
+        }
+        chunk_body
+    }
+
+    /// Get the 64bit body size of the chunk whose 32bit size is the placeholder.
+    pub(crate) fn chunk_size(&self, chunk_id: [u8; 4]) -> Option<u64> {
+        if chunk_id == [b'd', b'a', b't', b'a'] {
+            return Some(self.data_size);
+        }
+        self.table
+            .iter()
+            .find(|(table_chunk_id, _)| *table_chunk_id == chunk_id)
+            .map(|(_, chunk_size)| *chunk_size)
+    }
+}
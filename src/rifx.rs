@@ -0,0 +1,241 @@
+use std::borrow::Cow;
+
+use crate::adpcm::*;
+use crate::error::*;
+use crate::{SubChunk, WavFile, WaveFormat, WAVEFORMAT_ID_EXTENSIBLE};
+
+/// Reverse the byte order of the fields of "fmt" chunk. `is_big_endian` is the byte order of the chunk body before the conversion.
+/// The extension of WAVE_FORMAT_EXTENSIBLE (including the first 3 fields of the sub format GUID) and ADPCM are converted as well.
+pub(crate) fn swap_format_chunk(chunk_body: &[u8], is_big_endian: bool) -> Vec<u8> {
+    let mut swapped_body = chunk_body.to_vec();
+    if chunk_body.len() < 2 {
+        return swapped_body;
+    }
+    let format_id = if is_big_endian {
+        usize::from(u16::from_be_bytes([chunk_body[0], chunk_body[1]]))
+    } else {
+        usize::from(u16::from_le_bytes([chunk_body[0], chunk_body[1]]))
+    };
+    // format id, channel, sampling rate, byte per sec, block size, bit rate, extension size
+    let mut field_size_vec: Vec<usize> = vec![2, 2, 4, 4, 2, 2, 2];
+    if format_id == WAVEFORMAT_ID_EXTENSIBLE {
+        // valid bits, channel mask, sub format GUID (Data1, Data2, Data3)
+        field_size_vec.append(&mut vec![2, 4, 4, 2, 2]);
+    } else if is_adpcm_format(format_id) {
+        // samples per block (and the number of coefficients and the coefficients of MS ADPCM)
+        field_size_vec.append(&mut vec![2; chunk_body.len().saturating_sub(0x12) / 2]);
+    }
+    let mut pos = 0;
+    for field_size in field_size_vec {
+        if pos + field_size > swapped_body.len() {
+            break;
+        }
+        swapped_body[pos..pos + field_size].reverse();
+        pos += field_size;
+    }
+    swapped_body
+}
+
+/// Reverse the byte order of "fact" chunk. (32bit values)
+pub(crate) fn swap_fact_chunk(chunk_body: &[u8]) -> Vec<u8> {
+    let mut swapped_body = chunk_body.to_vec();
+    for value_bytes in swapped_body.chunks_exact_mut(4) {
+        value_bytes.reverse();
+    }
+    swapped_body
+}
+
+/// Reverse the byte order of "cue " chunk. The chunk id ("data") of each cue point is not changed.
+pub(crate) fn swap_cue_chunk(chunk_body: &[u8]) -> Vec<u8> {
+    let mut swapped_body = chunk_body.to_vec();
+    if swapped_body.len() < 4 {
+        return swapped_body;
+    }
+    // number of cue points
+    swapped_body[0..4].reverse();
+    // id, position, chunk id, chunk start, block start, sample offset
+    for cue_point_bytes in swapped_body[4..].chunks_exact_mut(24) {
+        for (field_idx, value_bytes) in cue_point_bytes.chunks_exact_mut(4).enumerate() {
+            if field_idx != 2 {
+                value_bytes.reverse();
+            }
+        }
+    }
+    swapped_body
+}
+
+/// Reverse the byte order of "smpl" chunk. `is_big_endian` is the byte order of the chunk body before the conversion.
+/// The sampler data is not changed.
+pub(crate) fn swap_smpl_chunk(chunk_body: &[u8], is_big_endian: bool) -> Vec<u8> {
+    let mut swapped_body = chunk_body.to_vec();
+    // 36 = header of 32bit values
+    if swapped_body.len() < 36 {
+        return swapped_body;
+    }
+    let loop_len_bytes = [chunk_body[28], chunk_body[29], chunk_body[30], chunk_body[31]];
+    let loop_len = if is_big_endian {
+        u32::from_be_bytes(loop_len_bytes)
+    } else {
+        u32::from_le_bytes(loop_len_bytes)
+    };
+    // 24 = size of a loop of 32bit values
+    let loops_end = usize::try_from(loop_len)
+        .unwrap_or(usize::MAX)
+        .saturating_mul(24)
+        .saturating_add(36)
+        .min(swapped_body.len());
+    for value_bytes in swapped_body[..loops_end].chunks_exact_mut(4) {
+        value_bytes.reverse();
+    }
+    swapped_body
+}
+
+/// Reverse the byte order of the time reference, the version and the loudness fields of "bext" chunk.
+pub(crate) fn swap_bext_chunk(chunk_body: &[u8]) -> Vec<u8> {
+    let mut swapped_body = chunk_body.to_vec();
+    // time reference (low, high), version, loudness value, loudness range, max true peak level,
+    // max momentary loudness, max short term loudness
+    for (pos, field_size) in [
+        (0x152, 4),
+        (0x156, 4),
+        (0x15a, 2),
+        (0x19c, 2),
+        (0x19e, 2),
+        (0x1a0, 2),
+        (0x1a2, 2),
+        (0x1a4, 2),
+    ] {
+        if pos + field_size > swapped_body.len() {
+            break;
+        }
+        swapped_body[pos..pos + field_size].reverse();
+    }
+    swapped_body
+}
+
+/// Reverse the byte order of the entry sizes of "LIST" chunk and the fields of "adtl" entries.
+/// `is_big_endian` is the byte order of the chunk body before the conversion. The lists other than "INFO" and "adtl" are not changed.
+pub(crate) fn swap_list_chunk(chunk_body: &[u8], is_big_endian: bool) -> Vec<u8> {
+    let mut swapped_body = chunk_body.to_vec();
+    let is_adtl = match chunk_body.get(0..4) {
+        Some(b"adtl") => true,
+        Some(b"INFO") => false,
+        _ => return swapped_body,
+    };
+    // 4 = list type
+    let mut pos = 4;
+    // 8 = chunk_id + body_size
+    while swapped_body.len() - pos >= 8 {
+        let chunk_id = [chunk_body[pos], chunk_body[pos + 1], chunk_body[pos + 2], chunk_body[pos + 3]];
+        let size_bytes = [
+            chunk_body[pos + 4],
+            chunk_body[pos + 5],
+            chunk_body[pos + 6],
+            chunk_body[pos + 7],
+        ];
+        let body_size = usize::try_from(if is_big_endian {
+            u32::from_be_bytes(size_bytes)
+        } else {
+            u32::from_le_bytes(size_bytes)
+        })
+        .unwrap_or(usize::MAX);
+        swapped_body[pos + 4..pos + 8].reverse();
+        let body_head = pos + 8;
+        let body_end = body_head.saturating_add(body_size).min(swapped_body.len());
+        if is_adtl {
+            let field_vec: &[(usize, usize)] = match &chunk_id {
+                // cue point id
+                b"labl" | b"note" | b"file" => &[(0, 4)],
+                // cue point id, sample length, (purpose id), country, language, dialect, code page
+                b"ltxt" => &[(0, 4), (4, 4), (12, 2), (14, 2), (16, 2), (18, 2)],
+                _ => &[],
+            };
+            for (field_pos, field_size) in field_vec {
+                if body_head + field_pos + field_size > body_end {
+                    break;
+                }
+                swapped_body[body_head + field_pos..body_head + field_pos + field_size].reverse();
+            }
+        }
+        // Odd-length body is followed by a pad byte.
+        pos = body_end.saturating_add(body_size % 2).min(swapped_body.len());
+    }
+    swapped_body
+}
+
+/// Reverse the byte order of the chunk body other than "data" chunk. `is_big_endian` is the byte order of the chunk body before the conversion.
+/// Return None when the structure of the chunk is not known, and the chunk is kept as it is.
+pub(crate) fn swap_chunk_body(chunk_id: [u8; 4], chunk_body: &[u8], is_big_endian: bool) -> Option<Vec<u8>> {
+    match &chunk_id {
+        b"fmt " => Some(swap_format_chunk(chunk_body, is_big_endian)),
+        b"fact" => Some(swap_fact_chunk(chunk_body)),
+        b"cue " => Some(swap_cue_chunk(chunk_body)),
+        b"smpl" => Some(swap_smpl_chunk(chunk_body, is_big_endian)),
+        b"bext" => Some(swap_bext_chunk(chunk_body)),
+        b"LIST" => Some(swap_list_chunk(chunk_body, is_big_endian)),
+        _ => None,
+    }
+}
+
+/// Reverse the byte order of each sample of "data" chunk. 8bit samples are not changed.
+pub(crate) fn swap_sample_bytes(wave_format: &WaveFormat, bytes_data_vec: &mut [u8]) -> Result<()> {
+    // ADPCM blocks have the headers in themselves.
+    if is_adpcm_format(wave_format.id) {
+        return Err(WavF64VecError::new(
+            WavF64VecErrorKind::FormatIsNotSupported,
+            Some("format id".to_string()),
+        ));
+    }
+    let size = wave_format.bits / 8;
+    if size > 1 {
+        for sample_bytes in bytes_data_vec.chunks_exact_mut(size) {
+            sample_bytes.reverse();
+        }
+    }
+    Ok(())
+}
+
+/// Convert the chunk bodies of RIFX to little endian. The chunks of unknown structure are kept as they are.
+pub(crate) fn swap_sub_chunks_from_rifx(sub_chunks: &mut [SubChunk]) -> Result<()> {
+    let mut op_wave_format: Option<WaveFormat> = None;
+    for sub_chunk in sub_chunks.iter_mut() {
+        if sub_chunk.chunk_id == [b'f', b'm', b't', b' '] {
+            sub_chunk.bytes_data_vec = swap_format_chunk(&sub_chunk.bytes_data_vec, true);
+            op_wave_format = WavFile::get_format_from_chunk(&sub_chunk.bytes_data_vec).ok();
+        }
+    }
+    for sub_chunk in sub_chunks.iter_mut() {
+        match sub_chunk.chunk_id {
+            [b'f', b'm', b't', b' '] => {}
+            [b'd', b'a', b't', b'a'] => {
+                if let Some(wave_format) = &op_wave_format {
+                    swap_sample_bytes(wave_format, &mut sub_chunk.bytes_data_vec)?;
+                }
+            }
+            _ => {
+                if let Some(swapped_body) = swap_chunk_body(sub_chunk.chunk_id, &sub_chunk.bytes_data_vec, true) {
+                    sub_chunk.bytes_data_vec = swapped_body;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Get the RIFX chunk body of the little endian sub chunk.
+pub(crate) fn rifx_chunk_body<'a>(sub_chunk: &'a SubChunk, op_wave_format: Option<&WaveFormat>) -> Result<Cow<'a, [u8]>> {
+    match sub_chunk.chunk_id {
+        [b'd', b'a', b't', b'a'] => match op_wave_format {
+            Some(wave_format) => {
+                let mut bytes_data_vec = sub_chunk.bytes_data_vec.clone();
+                swap_sample_bytes(wave_format, &mut bytes_data_vec)?;
+                Ok(Cow::Owned(bytes_data_vec))
+            }
+            None => Ok(Cow::Borrowed(&sub_chunk.bytes_data_vec)),
+        },
+        _ => match swap_chunk_body(sub_chunk.chunk_id, &sub_chunk.bytes_data_vec, false) {
+            Some(swapped_body) => Ok(Cow::Owned(swapped_body)),
+            None => Ok(Cow::Borrowed(&sub_chunk.bytes_data_vec)),
+        },
+    }
+}
@@ -0,0 +1,305 @@
+use crate::error::*;
+use crate::{SubChunk, WavFile};
+
+/// "smpl" chunk body size without the loops and the sampler data.
+/// * 4 = manufacturer
+/// * 4 = product
+/// * 4 = sample period
+/// * 4 = MIDI unity note
+/// * 4 = MIDI pitch fraction
+/// * 4 = SMPTE format
+/// * 4 = SMPTE offset
+/// * 4 = number of loops
+/// * 4 = sampler data size
+const SMPL_CHUNK_MIN_SIZE: usize = 36;
+
+/// Size of a loop in "smpl" chunk.
+/// * 4 = cue point id
+/// * 4 = type
+/// * 4 = start
+/// * 4 = end
+/// * 4 = fraction
+/// * 4 = play count
+const SAMPLE_LOOP_SIZE: usize = 24;
+
+/// "inst" chunk body size.
+const INST_CHUNK_SIZE: usize = 7;
+
+/// Loop type of "smpl" chunk.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum SampleLoopType {
+    /// Forward. (0)
+    #[default]
+    Forward,
+    /// Alternating forward and backward. (1)
+    PingPong,
+    /// Backward. (2)
+    Reverse,
+    /// Reserved or manufacturer specific type.
+    Other(u32),
+}
+
+impl SampleLoopType {
+    fn from_u32(loop_type: u32) -> SampleLoopType {
+        match loop_type {
+            0 => SampleLoopType::Forward,
+            1 => SampleLoopType::PingPong,
+            2 => SampleLoopType::Reverse,
+            loop_type => SampleLoopType::Other(loop_type),
+        }
+    }
+
+    fn to_u32(self) -> u32 {
+        match self {
+            SampleLoopType::Forward => 0,
+            SampleLoopType::PingPong => 1,
+            SampleLoopType::Reverse => 2,
+            SampleLoopType::Other(loop_type) => loop_type,
+        }
+    }
+}
+
+/// Loop of "smpl" chunk.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct SampleLoop {
+    /// Identifier of the cue point. (0 if none)
+    pub cue_point_id: u32,
+    /// Loop type.
+    pub loop_type: SampleLoopType,
+    /// Start sample offset (frames) of the loop.
+    pub start: u32,
+    /// End sample offset (frames) of the loop. The end sample is played.
+    pub end: u32,
+    /// Fraction of a sample for the loop point. (0x80000000 is a half sample)
+    pub fraction: u32,
+    /// Number of times to play the loop. 0 is infinite.
+    pub play_count: u32,
+}
+
+/// Sampler "smpl" chunk.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SmplChunk {
+    /// MIDI manufacturer code. (0 if none)
+    pub manufacturer: u32,
+    /// Product code of the manufacturer. (0 if none)
+    pub product: u32,
+    /// Sample period in nanoseconds. (e.g. 22675 for 44100Hz)
+    pub sample_period: u32,
+    /// MIDI note number which plays the sample at the original pitch. (0 to 127)
+    pub midi_unity_note: u32,
+    /// Fine tune above the unity note. (0x80000000 is 50 cents)
+    pub midi_pitch_fraction: u32,
+    /// SMPTE format. (0, 24, 25, 29 or 30)
+    pub smpte_format: u32,
+    /// SMPTE offset. (0xhhmmssff)
+    pub smpte_offset: u32,
+    /// Loops.
+    pub loop_vec: Vec<SampleLoop>,
+    /// Sampler specific data.
+    pub sampler_data: Vec<u8>,
+}
+
+impl SmplChunk {
+    /// Create structure. The unity note is 60 (C4).
+    pub fn new() -> SmplChunk {
+        SmplChunk {
+            midi_unity_note: 60,
+            ..Default::default()
+        }
+    }
+
+    /// Create structure with the "smpl" chunk.
+    pub fn from_sub_chunk(sub_chunk: &SubChunk) -> Result<SmplChunk> {
+        let chunk_body = &sub_chunk.bytes_data_vec;
+        if sub_chunk.chunk_id != [b's', b'm', b'p', b'l'] || chunk_body.len() < SMPL_CHUNK_MIN_SIZE {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"smpl\"".to_string()),
+            ));
+        }
+        let value_vec: Vec<u32> = chunk_body[0..SMPL_CHUNK_MIN_SIZE]
+            .chunks_exact(4)
+            .map(|value_bytes| u32::from_le_bytes([value_bytes[0], value_bytes[1], value_bytes[2], value_bytes[3]]))
+            .collect();
+        let loop_len = usize::try_from(value_vec[7])?;
+        let sampler_data_size = usize::try_from(value_vec[8])?;
+        let loops_end = SMPL_CHUNK_MIN_SIZE + loop_len * SAMPLE_LOOP_SIZE;
+        if (chunk_body.len() - SMPL_CHUNK_MIN_SIZE) / SAMPLE_LOOP_SIZE < loop_len
+            || chunk_body.len() - loops_end < sampler_data_size
+        {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"smpl\"".to_string()),
+            ));
+        }
+        let mut loop_vec: Vec<SampleLoop> = Vec::new();
+        for loop_bytes in chunk_body[SMPL_CHUNK_MIN_SIZE..loops_end].chunks_exact(SAMPLE_LOOP_SIZE) {
+            loop_vec.push(SampleLoop {
+                cue_point_id: u32::from_le_bytes(<[u8; 4]>::try_from(&loop_bytes[0x00..0x04])?),
+                loop_type: SampleLoopType::from_u32(u32::from_le_bytes(<[u8; 4]>::try_from(&loop_bytes[0x04..0x08])?)),
+                start: u32::from_le_bytes(<[u8; 4]>::try_from(&loop_bytes[0x08..0x0c])?),
+                end: u32::from_le_bytes(<[u8; 4]>::try_from(&loop_bytes[0x0c..0x10])?),
+                fraction: u32::from_le_bytes(<[u8; 4]>::try_from(&loop_bytes[0x10..0x14])?),
+                play_count: u32::from_le_bytes(<[u8; 4]>::try_from(&loop_bytes[0x14..0x18])?),
+            });
+        }
+        Ok(SmplChunk {
+            manufacturer: value_vec[0],
+            product: value_vec[1],
+            sample_period: value_vec[2],
+            midi_unity_note: value_vec[3],
+            midi_pitch_fraction: value_vec[4],
+            smpte_format: value_vec[5],
+            smpte_offset: value_vec[6],
+            loop_vec,
+            sampler_data: chunk_body[loops_end..loops_end + sampler_data_size].to_vec(),
+        })
+    }
+
+    /// Make "smpl" chunk.
+    pub fn to_sub_chunk(&self) -> Result<SubChunk> {
+        let mut chunk_body: Vec<u8> = Vec::new();
+        for value in [
+            self.manufacturer,
+            self.product,
+            self.sample_period,
+            self.midi_unity_note,
+            self.midi_pitch_fraction,
+            self.smpte_format,
+            self.smpte_offset,
+            u32::try_from(self.loop_vec.len())?,
+            u32::try_from(self.sampler_data.len())?,
+        ] {
+            chunk_body.extend_from_slice(&value.to_le_bytes());
+        }
+        for sample_loop in &self.loop_vec {
+            for value in [
+                sample_loop.cue_point_id,
+                sample_loop.loop_type.to_u32(),
+                sample_loop.start,
+                sample_loop.end,
+                sample_loop.fraction,
+                sample_loop.play_count,
+            ] {
+                chunk_body.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        chunk_body.extend_from_slice(&self.sampler_data);
+        Ok(SubChunk {
+            chunk_id: [b's', b'm', b'p', b'l'],
+            bytes_data_vec: chunk_body,
+        })
+    }
+}
+
+/// Instrument "inst" chunk.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InstChunk {
+    /// MIDI note number which plays the sample at the original pitch. (0 to 127)
+    pub unshifted_note: u8,
+    /// Fine tune in cents. (-50 to +50)
+    pub fine_tune: i8,
+    /// Gain in dB. (-64 to +64)
+    pub gain: i8,
+    /// Lowest MIDI note of the key range.
+    pub low_note: u8,
+    /// Highest MIDI note of the key range.
+    pub high_note: u8,
+    /// Lowest MIDI velocity of the velocity range.
+    pub low_velocity: u8,
+    /// Highest MIDI velocity of the velocity range.
+    pub high_velocity: u8,
+}
+
+impl Default for InstChunk {
+    fn default() -> Self {
+        InstChunk {
+            unshifted_note: 60,
+            fine_tune: 0,
+            gain: 0,
+            low_note: 0,
+            high_note: 127,
+            low_velocity: 1,
+            high_velocity: 127,
+        }
+    }
+}
+
+impl InstChunk {
+    /// Create structure. The unshifted note is 60 (C4), and the key and velocity ranges are full.
+    pub fn new() -> InstChunk {
+        Self::default()
+    }
+
+    /// Create structure with the "inst" chunk.
+    pub fn from_sub_chunk(sub_chunk: &SubChunk) -> Result<InstChunk> {
+        let chunk_body = &sub_chunk.bytes_data_vec;
+        if sub_chunk.chunk_id != [b'i', b'n', b's', b't'] || chunk_body.len() < INST_CHUNK_SIZE {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeError,
+                Some("\"inst\"".to_string()),
+            ));
+        }
+        Ok(InstChunk {
+            unshifted_note: chunk_body[0],
+            fine_tune: chunk_body[1] as i8,
+            gain: chunk_body[2] as i8,
+            low_note: chunk_body[3],
+            high_note: chunk_body[4],
+            low_velocity: chunk_body[5],
+            high_velocity: chunk_body[6],
+        })
+    }
+
+    /// Make "inst" chunk.
+    pub fn to_sub_chunk(&self) -> SubChunk {
+        SubChunk {
+            chunk_id: [b'i', b'n', b's', b't'],
+            bytes_data_vec: vec![
+                self.unshifted_note,
+                self.fine_tune as u8,
+                self.gain as u8,
+                self.low_note,
+                self.high_note,
+                self.low_velocity,
+                self.high_velocity,
+            ],
+        }
+    }
+}
+
+impl WavFile {
+    /// Get "smpl" chunk. If it does not exist, return None.
+    pub fn get_smpl(&self) -> Result<Option<SmplChunk>> {
+        match self
+            .sub_chunks
+            .iter()
+            .find(|sub_chunk| sub_chunk.chunk_id == [b's', b'm', b'p', b'l'])
+        {
+            Some(sub_chunk) => Ok(Some(SmplChunk::from_sub_chunk(sub_chunk)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Update "smpl" chunk. If it does not exist, the chunk is added.
+    pub fn update_smpl(&mut self, smpl_chunk: &SmplChunk) -> Result<()> {
+        self.update_sub_chunk(smpl_chunk.to_sub_chunk()?)
+    }
+
+    /// Get "inst" chunk. If it does not exist, return None.
+    pub fn get_inst(&self) -> Result<Option<InstChunk>> {
+        match self
+            .sub_chunks
+            .iter()
+            .find(|sub_chunk| sub_chunk.chunk_id == [b'i', b'n', b's', b't'])
+        {
+            Some(sub_chunk) => Ok(Some(InstChunk::from_sub_chunk(sub_chunk)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Update "inst" chunk. If it does not exist, the chunk is added.
+    pub fn update_inst(&mut self, inst_chunk: &InstChunk) -> Result<()> {
+        self.update_sub_chunk(inst_chunk.to_sub_chunk())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::*;
+use crate::{BIT16_WAVE_DIVISOR, BIT24_WAVE_DIVISOR, BIT32_WAVE_DIVISOR, BIT8_WAVE_DIVISOR};
+
+/// Scaling convention between audio data values (`f64`) and integer PCM values.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum PcmScaling {
+    /// Full scale is 2^(n-1) - 1. +1.0 and -1.0 are `MAX` and `-MAX`. (`MIN` is slightly below -1.0.)
+    Symmetric,
+    /// Full scale is 2^(n-1). -1.0 is `MIN`, and +1.0 is clipped to `MAX`.
+    #[default]
+    Asymmetric,
+    /// Full scale is the value. For example, `Custom(32767.5)`.
+    Custom(f64),
+}
+
+impl PcmScaling {
+    /// Get the value which is scaled to 1.0.
+    pub fn full_scale(&self, bits: usize) -> Result<f64> {
+        let (_, max_val) = pcm_range(bits / 8)?;
+        match self {
+            PcmScaling::Symmetric => Ok(max_val),
+            PcmScaling::Asymmetric => Ok(max_val + 1.0),
+            PcmScaling::Custom(full_scale) => {
+                if full_scale.is_finite() && *full_scale > 0.0 {
+                    Ok(*full_scale)
+                } else {
+                    Err(WavF64VecError::new(
+                        WavF64VecErrorKind::ParameterIsOutOfRange,
+                        Some("full scale".to_string()),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Minimum and maximum of the integer PCM value of the bytes length.
+pub(crate) fn pcm_range(bytes_len: usize) -> Result<(f64, f64)> {
+    let divisor = match bytes_len {
+        1 => BIT8_WAVE_DIVISOR as f64,
+        2 => BIT16_WAVE_DIVISOR as f64,
+        3 => BIT24_WAVE_DIVISOR as f64,
+        4 => BIT32_WAVE_DIVISOR as f64,
+        _ => return Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None)),
+    };
+    Ok((-divisor, divisor - 1.0))
+}
+
+/// Convert from a bytes data of integer PCM to the integer value.
+pub(crate) fn bytes_to_pcm(bytes: &[u8]) -> Result<i32> {
+    match bytes.len() {
+        //unsigned 8bit
+        1 => Ok(i32::from(bytes[0]) - 128),
+        //signed 16bit
+        2 => Ok(i32::from(i16::from_le_bytes(<[u8; 2]>::try_from(bytes)?))),
+        //signed 24bit
+        3 => Ok(i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8),
+        //signed 32bit
+        4 => Ok(i32::from_le_bytes(<[u8; 4]>::try_from(bytes)?)),
+        _ => Err(WavF64VecError::new(WavF64VecErrorKind::BytesLengthError, None)),
+    }
+}
+
+/// Convert from the integer PCM value (rounded and clipped) to a bytes data vector.
+pub(crate) fn pcm_to_bytes(pcm_val: f64, bytes_len: usize) -> Result<Vec<u8>> {
+    let (min_val, max_val) = pcm_range(bytes_len)?;
+    let i32_val = pcm_val.round().clamp(min_val, max_val) as i32;
+    if bytes_len == 1 {
+        //to unsigned 8bit
+        Ok(vec![(i32_val + 128) as u8])
+    } else {
+        Ok(i32_val.to_le_bytes()[0..bytes_len].to_vec())
+    }
+}
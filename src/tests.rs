@@ -2,11 +2,32 @@
 mod tests {
     use crate::SubChunk;
 
-    use super::super::error::*;
+    use super::super::BextChunk;
+    use super::super::CuePoint;
+    use super::super::FactChunk;
+    use super::super::WavContainer;
     use super::super::WavFile;
+    use super::super::WavReader;
+    use super::super::WavRepairWarning;
+    use super::super::WavWriter;
     use super::super::WaveFormat;
+    use super::super::WaveFormatExtension;
+    use super::super::W64_GUID_CHUNK_ID;
+    use super::super::{
+        bytes_to_f64wave, bytes_to_f64wave_with_scaling, f64wave_to_bytes, f64wave_to_bytes_with_scaling, PcmScaling,
+    };
     use super::super::{convert_sampling_rate_for_channel_data_vec, convert_sampling_rate_for_data_channel_vec};
+    use super::super::{
+        resample_for_channel_data_vec, resample_for_data_channel_vec, ResampleOptions, ResampleQuality, Resampler,
+    };
+    use super::super::{AiffContainer, AiffFile};
+    use super::super::{DecodeOptions, Dither, EncodeOptions, NoiseShaping};
+    use super::super::{InfoList, INFO_ID_GENRE};
+    use super::super::{InstChunk, SampleLoop, SampleLoopType, SmplChunk};
+    use super::super::{WavF64VecError, WavF64VecErrorKind};
     use super::super::{BIT16_WAVE_DIVISOR, BIT24_WAVE_DIVISOR, BIT32_WAVE_DIVISOR, BIT8_WAVE_DIVISOR};
+    use super::super::{SPEAKER_FRONT_CENTER, SPEAKER_FRONT_LEFT, SPEAKER_FRONT_RIGHT, SPEAKER_SIDE_LEFT, SPEAKER_SIDE_RIGHT};
+    use super::super::{WAVEFORMAT_ID_ALAW, WAVEFORMAT_ID_IMA_ADPCM, WAVEFORMAT_ID_MS_ADPCM, WAVEFORMAT_ID_MULAW};
     use std::fs::{remove_file, File};
     use std::io::prelude::*;
     use std::io::BufReader;
@@ -27,13 +48,14 @@ mod tests {
             let path_buf = create_test_file(1, 1, 8000, 8, &written_channel_vec);
             #[rustfmt::skip]
             let (vec_of_fmt_chunk, vec_of_data_chunk, vec_of_file) = create_u8_vecs(
-                &[0x2F, 0x00, 0x00, 0x00],
+                &[0x30, 0x00, 0x00, 0x00],
                 &[0x01, 0x00],
                 &[0x01, 0x00],
                 &[0x40, 0x1F, 0x00, 0x00],
                 &[0x40, 0x1F, 0x00, 0x00],
                 &[0x01, 0x00],
                 &[0x08, 0x00],
+                &[],
                 &[0x0B, 0x00, 0x00, 0x00],
                 &[
                     0x80, 0xC0, 0xFF,
@@ -72,6 +94,7 @@ mod tests {
                 &[0x00, 0xFA, 0x00, 0x00],
                 &[0x04, 0x00],
                 &[0x10, 0x00],
+                &[],
                 &[0x2C, 0x00, 0x00, 0x00],
                 &[
                     0x00, 0x00, 0x00, 0x00,
@@ -91,7 +114,7 @@ mod tests {
         }
 
         {
-            // formatid:1 , bits per sample:24, channel:1, sampling rate:22050Hz,
+            // formatid:0xFFFE(PCM) , bits per sample:24, channel:1, sampling rate:22050Hz,
             let half = (0x400000i32) as f64 / BIT24_WAVE_DIVISOR as f64;
             let max_plus = (BIT24_WAVE_DIVISOR - 1) as f64 / BIT24_WAVE_DIVISOR as f64;
             let written_channel_vec: Vec<Vec<f64>> =
@@ -102,13 +125,17 @@ mod tests {
             let path_buf = create_test_file(1, 1, 22050, 24, &written_channel_vec);
             #[rustfmt::skip]
             let (vec_of_fmt_chunk, vec_of_data_chunk, vec_of_file) = create_u8_vecs(
-                &[0x45, 0x00, 0x00, 0x00],
-                &[0x01, 0x00],
+                &[0x5E, 0x00, 0x00, 0x00],
+                &[0xFE, 0xFF],
                 &[0x01, 0x00],
                 &[0x22, 0x56, 0x00, 0x00],
                 &[0x66, 0x02, 0x01, 0x00],
                 &[0x03, 0x00],
                 &[0x18, 0x00],
+                &[
+                    0x16, 0x00, 0x18, 0x00, 0x04, 0x00, 0x00, 0x00,
+                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+                ],
                 &[0x21, 0x00, 0x00, 0x00],
                 &[
                     0x00, 0x00, 0x00, 0x00,
@@ -127,7 +154,7 @@ mod tests {
         }
 
         {
-            // formatid:1 , bits per sample:32, channel:1, sampling rate:44100Hz,
+            // formatid:0xFFFE(PCM) , bits per sample:32, channel:1, sampling rate:44100Hz,
             let half = (0x40000000i32) as f64 / BIT32_WAVE_DIVISOR as f64;
             let max_plus = i32::MAX as f64 / BIT32_WAVE_DIVISOR as f64;
             let written_channel_vec: Vec<Vec<f64>> =
@@ -138,13 +165,17 @@ mod tests {
             let path_buf = create_test_file(1, 1, 44100, 32, &written_channel_vec);
             #[rustfmt::skip]
             let (vec_of_fmt_chunk, vec_of_data_chunk, vec_of_file) = create_u8_vecs(
-                &[0x50, 0x00, 0x00, 0x00],
-                &[0x01, 0x00],
+                &[0x68, 0x00, 0x00, 0x00],
+                &[0xFE, 0xFF],
                 &[0x01, 0x00],
                 &[0x44, 0xAC, 0x00, 0x00],
                 &[0x10, 0xB1, 0x02, 0x00],
                 &[0x04, 0x00],
                 &[0x20, 0x00],
+                &[
+                    0x16, 0x00, 0x20, 0x00, 0x04, 0x00, 0x00, 0x00,
+                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+                ],
                 &[0x2C, 0x00, 0x00, 0x00],
                 &[
                     0x00, 0x00, 0x00, 0x00,
@@ -195,13 +226,14 @@ mod tests {
             let path_buf = create_test_file(3, 1, 192000, 32, &written_channel_vec);
             #[rustfmt::skip]
             let (vec_of_fmt_chunk, vec_of_data_chunk, vec_of_file) = create_u8_vecs(
-                &[0x50, 0x00, 0x00, 0x00],
+                &[0x5C, 0x00, 0x00, 0x00],
                 &[0x03, 0x00],
                 &[0x01, 0x00],
                 &[0x00, 0xEE, 0x02, 0x00],
                 &[0x00, 0xB8, 0x0B, 0x00],
                 &[0x04, 0x00],
                 &[0x20, 0x00],
+                &[],
                 &[0x2C, 0x00, 0x00, 0x00],
                 &[
                     0x00, 0x00, 0x00, 0x00,
@@ -394,10 +426,11 @@ mod tests {
         // 8 = junk chunk_id + body_size
         // 24 = "fmt" chunk size
         // 8 = data chunk_id + body_size
-        // 1 = audio data
+        // 2 = audio data + pad byte
+        // 1 = odd file size is impossible because of the pad bytes.
         let junk_chunk = SubChunk {
             chunk_id: [b'J', b'U', b'N', b'K'],
-            bytes_data_vec: vec![0x00; 0xffffffff - 12 - 8 - 24 - 8 - 1],
+            bytes_data_vec: vec![0x00; 0xffffffff - 12 - 8 - 24 - 8 - 2 - 1],
         };
 
         let wave_format = WaveFormat {
@@ -419,24 +452,24 @@ mod tests {
         remove_file(Path::new("./large_files.wav")).unwrap();
         // Err
         channel_data_vec[0].push(0.0);
+        channel_data_vec[0].push(0.0);
         let result = wav_file.update_audio_for_channel_data_vec(&wave_format, &channel_data_vec);
         match result {
             Ok(_) => {
                 panic!();
             }
-            Err(err) => {
-                if let Some(wav_err) = err.downcast_ref::<WavF64VecError>() {
-                    if wav_err.err_kind == WavF64VecErrorKind::SubChunkSizeTooLarge {
-                        if wav_err.op_additional_message.as_ref().unwrap() != "data" {
-                            panic!();
-                        }
-                    } else {
-                        panic!();
-                    }
-                } else {
+            Err(WavF64VecError::Format {
+                err_kind: WavF64VecErrorKind::SubChunkSizeTooLarge,
+                op_additional_message,
+                ..
+            }) => {
+                if op_additional_message.unwrap() != "data" {
                     panic!();
                 }
             }
+            Err(_) => {
+                panic!();
+            }
         }
 
         // Err
@@ -446,19 +479,18 @@ mod tests {
             Ok(_) => {
                 panic!();
             }
-            Err(err) => {
-                if let Some(wav_err) = err.downcast_ref::<WavF64VecError>() {
-                    if wav_err.err_kind == WavF64VecErrorKind::SubChunkSizeTooLarge {
-                        if wav_err.op_additional_message.is_some() {
-                            panic!();
-                        }
-                    } else {
-                        panic!();
-                    }
-                } else {
+            Err(WavF64VecError::Format {
+                err_kind: WavF64VecErrorKind::SubChunkSizeTooLarge,
+                op_additional_message,
+                ..
+            }) => {
+                if op_additional_message.is_some() {
                     panic!();
                 }
             }
+            Err(_) => {
+                panic!();
+            }
         }
 
         // Err
@@ -470,19 +502,18 @@ mod tests {
             Ok(_) => {
                 panic!();
             }
-            Err(err) => {
-                if let Some(wav_err) = err.downcast_ref::<WavF64VecError>() {
-                    if wav_err.err_kind == WavF64VecErrorKind::SubChunkSizeTooLarge {
-                        if wav_err.op_additional_message.as_ref().unwrap() != "fmt " {
-                            panic!();
-                        }
-                    } else {
-                        panic!();
-                    }
-                } else {
+            Err(WavF64VecError::Format {
+                err_kind: WavF64VecErrorKind::SubChunkSizeTooLarge,
+                op_additional_message,
+                ..
+            }) => {
+                if op_additional_message.unwrap() != "fmt " {
                     panic!();
                 }
             }
+            Err(_) => {
+                panic!();
+            }
         }
     }
 
@@ -542,109 +573,2453 @@ mod tests {
         assert_eq!(wav_file.get_format().unwrap().unwrap(), wave_format);
     }
 
-    fn create_test_file(
-        id: usize,
-        channel: usize,
-        sampling_rate: usize,
-        bits: usize,
-        channel_data_vec: &Vec<Vec<f64>>,
-    ) -> PathBuf {
+    #[test]
+    fn multi_channel_test() {
+        for (channel, bits) in [(6, 16), (8, 24)] {
+            // channel_idx / 8 is exactly representable in each bits.
+            let channel_data_vec: Vec<Vec<f64>> = (0..channel)
+                .map(|channel_idx| {
+                    let val = channel_idx as f64 / 8.0;
+                    vec![0.00, val, -val, 0.50, -0.50, val, 0.00]
+                })
+                .collect();
+            let data_channel_vec: Vec<Vec<f64>> = (0..channel_data_vec[0].len())
+                .map(|data_idx| channel_data_vec.iter().map(|data_vec| data_vec[data_idx]).collect())
+                .collect();
+
+            // channel data vec round trip
+            let path_buf = create_test_file(1, channel, 48000, bits, &channel_data_vec);
+            let wav_file = WavFile::open(&path_buf).unwrap();
+            let (wave_format, read_channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+            assert_eq!(wave_format.channel, channel);
+            assert_eq!(read_channel_data_vec, channel_data_vec);
+            let (_, read_data_channel_vec) = wav_file.get_audio_for_data_channel_vec().unwrap();
+            assert_eq!(read_data_channel_vec, data_channel_vec);
+            remove_file(&path_buf).unwrap();
+
+            // data channel vec round trip
+            let mut wav_file = WavFile::new();
+            wav_file
+                .update_audio_for_data_channel_vec(&wave_format, &data_channel_vec)
+                .unwrap();
+            let (_, read_channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+            assert_eq!(read_channel_data_vec, channel_data_vec);
+
+            // convert sampling rate
+            let new_channel_data_vec = convert_sampling_rate_for_channel_data_vec(&channel_data_vec, 48000, 24000).unwrap();
+            let new_data_channel_vec = convert_sampling_rate_for_data_channel_vec(&data_channel_vec, 48000, 24000).unwrap();
+            assert_eq!(new_channel_data_vec.len(), channel);
+            assert_eq!(new_data_channel_vec[0].len(), channel);
+            for (channel_idx, data_vec) in new_channel_data_vec.iter().enumerate() {
+                let val = channel_idx as f64 / 8.0;
+                assert_eq!(*data_vec, vec![0.00, -val, -0.50, 0.00]);
+                for (data_idx, data) in data_vec.iter().enumerate() {
+                    assert_eq!(new_data_channel_vec[data_idx][channel_idx], *data);
+                }
+            }
+        }
+
+        // channel length mismatch
         let wave_format = WaveFormat {
-            id: id,
-            channel: channel,
-            sampling_rate: sampling_rate,
-            bits: bits,
+            id: 1,
+            channel: 4,
+            sampling_rate: 48000,
+            bits: 16,
+        };
+        let mut wav_file = WavFile::new();
+        assert!(wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.0]; 3])
+            .is_err());
+        assert!(wav_file
+            .update_audio_for_data_channel_vec(&wave_format, &vec![vec![0.0; 5]; 3])
+            .is_err());
+    }
+
+    #[test]
+    fn extensible_format_test() {
+        let fmt_chunk_id = [b'f', b'm', b't', b' '];
+
+        // 16bit stereo: plain "fmt" chunk
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 48000,
+            bits: 16,
         };
         let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.0; 4]; 2])
+            .unwrap();
+        let fmt_chunk_idx = wav_file.get_sub_chunk_idx(fmt_chunk_id).unwrap();
+        assert_eq!(wav_file.sub_chunks[fmt_chunk_idx].bytes_data_vec.len(), 16);
+        assert_eq!(wav_file.get_format_extension().unwrap(), None);
+
+        // Custom speaker positions: extensible "fmt" chunk
+        let extension = WaveFormatExtension {
+            valid_bits: 12,
+            channel_mask: SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT,
+        };
+        wav_file.update_format_extension(&extension).unwrap();
+        assert_eq!(wav_file.sub_chunks[fmt_chunk_idx].bytes_data_vec.len(), 40);
+        assert_eq!(wav_file.get_format().unwrap().unwrap(), wave_format);
+        assert_eq!(wav_file.get_format_extension().unwrap(), Some(extension));
+
+        // The extension is kept while the channel number does not change.
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.5; 8]; 2])
+            .unwrap();
+        assert_eq!(wav_file.get_format_extension().unwrap(), Some(extension));
+
+        // The valid bits follow the new bit depth, and the speaker positions are kept.
+        let mut bits_wav_file = wav_file.clone();
+        for (id, bits) in [(1, 24), (1, 32), (3, 32), (3, 64)] {
+            let new_format = WaveFormat { id, bits, ..wave_format };
+            bits_wav_file
+                .update_audio_for_channel_data_vec(&new_format, &vec![vec![0.5; 8]; 2])
+                .unwrap();
+            assert_eq!(
+                bits_wav_file.get_format_extension().unwrap(),
+                Some(WaveFormatExtension {
+                    valid_bits: bits,
+                    channel_mask: SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT,
+                })
+            );
+        }
+
+        // Invalid extensions
+        let too_many_bits = WaveFormatExtension {
+            valid_bits: 24,
+            channel_mask: 0,
+        };
+        assert!(wav_file.update_format_extension(&too_many_bits).is_err());
+        let too_many_speakers = WaveFormatExtension {
+            valid_bits: 16,
+            channel_mask: SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_CENTER,
+        };
+        assert!(wav_file.update_format_extension(&too_many_speakers).is_err());
+
+        // 5.1ch: extensible "fmt" chunk with the default speaker positions
+        let wave_format = WaveFormat {
+            id: 3,
+            channel: 6,
+            sampling_rate: 48000,
+            bits: 32,
+        };
+        let channel_data_vec = vec![vec![0.25, -0.25]; 6];
         wav_file
             .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
             .unwrap();
-        let path_string = format!(
-            "./test_id{}_{}ch_{}hz_{}bits.wav",
-            wave_format.id, wave_format.channel, wave_format.sampling_rate, wave_format.bits
+        #[rustfmt::skip]
+        assert_eq!(
+            wav_file.sub_chunks[fmt_chunk_idx].bytes_data_vec,
+            vec![
+                0xFE, 0xFF, 0x06, 0x00, 0x80, 0xBB, 0x00, 0x00, 0x00, 0x94, 0x11, 0x00, 0x18, 0x00, 0x20, 0x00,
+                0x16, 0x00, 0x20, 0x00, 0x3F, 0x00, 0x00, 0x00,
+                0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+            ]
+        );
+        assert_eq!(
+            wav_file.get_format_extension().unwrap(),
+            Some(WaveFormatExtension {
+                valid_bits: 32,
+                channel_mask: 0x3F,
+            })
+        );
+        assert_eq!(
+            wav_file.get_audio_for_channel_data_vec().unwrap(),
+            (wave_format, channel_data_vec)
         );
-        wav_file.save_as(Path::new(&path_string)).unwrap();
-        PathBuf::from(&path_string)
     }
 
-    fn create_test_file_from_channel_vec(
-        id: usize,
-        channel: usize,
-        sampling_rate: usize,
-        bits: usize,
-        channel_data_vec: &Vec<Vec<f64>>,
-    ) -> PathBuf {
+    #[test]
+    fn float64_test() {
+        // Values which are not representable in 32bit float.
+        let written_channel_vec: Vec<Vec<f64>> = vec![
+            vec![0.1, -0.1, 1.0 / 3.0, f64::MAX, f64::MIN_POSITIVE, 2.5],
+            vec![-0.7, 0.7, -1.0 / 3.0, f64::MIN, -f64::MIN_POSITIVE, -2.5],
+            vec![f64::EPSILON, 0.0, 1.0, -1.0, 1e-300, -1e300],
+        ];
+
+        // 2ch: plain "fmt" chunk
+        let path_buf = create_test_file(3, 2, 96000, 64, &written_channel_vec[0..2].to_vec());
+        #[rustfmt::skip]
+        let (vec_of_fmt_chunk, _, _) = create_u8_vecs(
+            &[],
+            &[0x03, 0x00],
+            &[0x02, 0x00],
+            &[0x00, 0x77, 0x01, 0x00],
+            &[0x00, 0x70, 0x17, 0x00],
+            &[0x10, 0x00],
+            &[0x40, 0x00],
+            &[],
+            &[],
+            &[],
+        );
+        let wav_file = WavFile::open(&path_buf).unwrap();
+        assert_eq!(wav_file.sub_chunks[0].bytes_data_vec, vec_of_fmt_chunk);
+        let (wave_format, channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+        assert_eq!(wave_format.bits, 64);
+        assert_eq!(channel_data_vec, written_channel_vec[0..2].to_vec());
+        remove_file(&path_buf).unwrap();
+
+        // 3ch: extensible "fmt" chunk
+        let path_buf = create_test_file(3, 3, 96000, 64, &written_channel_vec);
+        let wav_file = WavFile::open(&path_buf).unwrap();
+        assert!(wav_file.get_format_extension().unwrap().is_some());
+        let (wave_format, channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+        assert_eq!(wave_format.id, 3);
+        assert_eq!(channel_data_vec, written_channel_vec);
+        remove_file(&path_buf).unwrap();
+    }
+
+    #[test]
+    fn streaming_reader_test() {
+        let channel_data_vec: Vec<Vec<f64>> = (0..3)
+            .map(|channel_idx| {
+                (0..10)
+                    .map(|data_idx| (data_idx as f64 - channel_idx as f64) / 16.0)
+                    .collect()
+            })
+            .collect();
         let wave_format = WaveFormat {
-            id: id,
-            channel: channel,
-            sampling_rate: sampling_rate,
-            bits: bits,
+            id: 1,
+            channel: 3,
+            sampling_rate: 44100,
+            bits: 16,
+        };
+        let junk_chunk = SubChunk {
+            chunk_id: [b'J', b'U', b'N', b'K'],
+            bytes_data_vec: vec![0x00; 4],
+        };
+        let list_chunk = SubChunk {
+            chunk_id: [b'L', b'I', b'S', b'T'],
+            bytes_data_vec: vec![b'I', b'N', b'F', b'O'],
         };
         let mut wav_file = WavFile::new();
+        wav_file.update_sub_chunk(junk_chunk.clone()).unwrap();
         wav_file
             .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
             .unwrap();
-        wav_file.save_as(Path::new("./test_channel_vec.wav")).unwrap();
-        PathBuf::from(&"./test_channel_vec.wav")
+        wav_file.update_sub_chunk(list_chunk.clone()).unwrap();
+        wav_file.save_as(Path::new("./test_streaming_reader.wav")).unwrap();
+
+        let mut wav_reader = WavReader::open(Path::new("./test_streaming_reader.wav")).unwrap();
+        assert_eq!(wav_reader.get_format(), wave_format);
+        assert_eq!(wav_reader.get_frame_len(), 10);
+        let sub_chunk_id_vec: Vec<[u8; 4]> = wav_reader
+            .get_sub_chunks()
+            .iter()
+            .map(|sub_chunk| sub_chunk.chunk_id)
+            .collect();
+        assert_eq!(
+            sub_chunk_id_vec,
+            vec![junk_chunk.chunk_id, [b'f', b'm', b't', b' '], list_chunk.chunk_id]
+        );
+        assert_eq!(wav_reader.get_sub_chunks()[2], list_chunk);
+
+        // channel data vec by 4 frames
+        let mut read_channel_data_vec: Vec<Vec<f64>> = vec![Vec::new(); 3];
+        loop {
+            let block = wav_reader.read_audio_for_channel_data_vec(4).unwrap();
+            if block[0].is_empty() {
+                break;
+            }
+            assert!(block[0].len() <= 4);
+            for (channel_idx, data_vec) in block.into_iter().enumerate() {
+                read_channel_data_vec[channel_idx].extend(data_vec);
+            }
+        }
+        assert_eq!(read_channel_data_vec, channel_data_vec);
+        assert_eq!(wav_reader.get_frame_pos(), 10);
+
+        // data channel vec from the middle
+        wav_reader.seek_frame(7).unwrap();
+        let block = wav_reader.read_audio_for_data_channel_vec(4).unwrap();
+        assert_eq!(block.len(), 3);
+        for (data_idx, channel_vec) in block.iter().enumerate() {
+            for (channel_idx, data) in channel_vec.iter().enumerate() {
+                assert_eq!(*data, channel_data_vec[channel_idx][7 + data_idx]);
+            }
+        }
+        assert!(wav_reader.read_audio_for_data_channel_vec(4).unwrap().is_empty());
+
+        // The scaling of the options
+        let options = DecodeOptions {
+            scaling: PcmScaling::Symmetric,
+        };
+        let (_, expected_channel_data_vec) = WavFile::open(Path::new("./test_streaming_reader.wav"))
+            .unwrap()
+            .get_audio_for_channel_data_vec_with_options(&options)
+            .unwrap();
+        assert_ne!(expected_channel_data_vec, channel_data_vec);
+        wav_reader.seek_frame(0).unwrap();
+        assert_eq!(
+            wav_reader.read_audio_for_channel_data_vec_with_options(10, &options).unwrap(),
+            expected_channel_data_vec
+        );
+        wav_reader.seek_frame(3).unwrap();
+        let block = wav_reader.read_audio_for_data_channel_vec_with_options(1, &options).unwrap();
+        assert_eq!(
+            block,
+            vec![expected_channel_data_vec
+                .iter()
+                .map(|data_vec| data_vec[3])
+                .collect::<Vec<f64>>()]
+        );
+        remove_file(Path::new("./test_streaming_reader.wav")).unwrap();
     }
 
-    fn create_test_file_from_data_vec(
-        id: usize,
-        channel: usize,
-        sampling_rate: usize,
-        bits: usize,
-        data_channel_vec: &Vec<Vec<f64>>,
-    ) -> PathBuf {
+    #[test]
+    fn streaming_writer_test() {
+        let channel_data_vec: Vec<Vec<f64>> = (0..3)
+            .map(|channel_idx| {
+                (0..10)
+                    .map(|data_idx| (data_idx as f64 - channel_idx as f64) / 16.0)
+                    .collect()
+            })
+            .collect();
         let wave_format = WaveFormat {
-            id: id,
-            channel: channel,
-            sampling_rate: sampling_rate,
-            bits: bits,
+            id: 1,
+            channel: 3,
+            sampling_rate: 44100,
+            bits: 24,
         };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        wav_file.save_as(Path::new("./test_streaming_writer_expected.wav")).unwrap();
+        let mut expected_buf = Vec::new();
+        File::open("./test_streaming_writer_expected.wav")
+            .unwrap()
+            .read_to_end(&mut expected_buf)
+            .unwrap();
+        remove_file(Path::new("./test_streaming_writer_expected.wav")).unwrap();
+
+        // channel data vec by 4 frames
+        let mut wav_writer = WavWriter::create(Path::new("./test_streaming_writer.wav"), &wave_format).unwrap();
+        for data_idx in (0..10).step_by(4) {
+            let block: Vec<Vec<f64>> = channel_data_vec
+                .iter()
+                .map(|data_vec| data_vec[data_idx..(data_idx + 4).min(10)].to_vec())
+                .collect();
+            wav_writer.write_audio_for_channel_data_vec(&block).unwrap();
+        }
+        assert_eq!(wav_writer.get_frame_len(), 10);
+        wav_writer.finalize().unwrap();
+        let mut buf = Vec::new();
+        File::open("./test_streaming_writer.wav")
+            .unwrap()
+            .read_to_end(&mut buf)
+            .unwrap();
+        assert_eq!(buf, expected_buf);
+        remove_file(Path::new("./test_streaming_writer.wav")).unwrap();
+
+        // data channel vec by 3 frames, and sizes are fixed on drop
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        {
+            let mut wav_writer = WavWriter::new(&mut cursor, &wave_format).unwrap();
+            for data_idx in (0..10).step_by(3) {
+                let block: Vec<Vec<f64>> = (data_idx..(data_idx + 3).min(10))
+                    .map(|idx| channel_data_vec.iter().map(|data_vec| data_vec[idx]).collect())
+                    .collect();
+                wav_writer.write_audio_for_data_channel_vec(&block).unwrap();
+            }
+        }
+        assert_eq!(cursor.into_inner(), expected_buf);
+
+        // Mismatched channel number
+        let mut wav_writer = WavWriter::new(std::io::Cursor::new(Vec::new()), &wave_format).unwrap();
+        assert!(wav_writer.write_audio_for_channel_data_vec(&vec![vec![0.0]; 2]).is_err());
+
+        // Samples which are not in whole bytes
+        for bits in [4, 12] {
+            let wave_format = WaveFormat {
+                id: 1,
+                channel: 1,
+                sampling_rate: 44100,
+                bits,
+            };
+            let err = WavWriter::new(std::io::Cursor::new(Vec::new()), &wave_format).err().unwrap();
+            assert_eq!(err.err_kind(), Some(&WavF64VecErrorKind::FormatIsNotSupported));
+        }
+    }
+
+    #[test]
+    fn reader_writer_bytes_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50, 0.25], vec![0.25, -0.25, 0.00, 0.50]];
+        let path_buf = create_test_file(1, 2, 8000, 16, &channel_data_vec);
+        let mut file_buf = Vec::new();
+        File::open(&path_buf).unwrap().read_to_end(&mut file_buf).unwrap();
+        let wav_file = WavFile::open(&path_buf).unwrap();
+        remove_file(&path_buf).unwrap();
+
+        // bytes
+        let bytes_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(bytes_wav_file.sub_chunks, wav_file.sub_chunks);
+        assert_eq!(bytes_wav_file.file_path, PathBuf::new());
+        assert_eq!(bytes_wav_file.to_bytes().unwrap(), file_buf);
+
+        // reader & writer
+        let reader_wav_file = WavFile::from_reader(std::io::Cursor::new(&file_buf)).unwrap();
+        assert_eq!(reader_wav_file.sub_chunks, wav_file.sub_chunks);
+        let mut written_buf: Vec<u8> = Vec::new();
+        reader_wav_file.write_to(&mut written_buf).unwrap();
+        assert_eq!(written_buf, file_buf);
+        assert_eq!(
+            reader_wav_file.get_audio_for_channel_data_vec().unwrap(),
+            wav_file.get_audio_for_channel_data_vec().unwrap()
+        );
+
+        // Too short or wrong bytes
+        assert!(WavFile::from_bytes(&[]).is_err());
+        assert!(WavFile::from_bytes(&file_buf[0..8]).is_err());
+        assert!(WavFile::from_bytes(&file_buf[0..file_buf.len() - 1]).is_err());
+    }
 
+    #[test]
+    fn rf64_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50, 0.25], vec![0.25, -0.25, 0.00, 0.50]];
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 8000,
+            bits: 16,
+        };
         let mut wav_file = WavFile::new();
         wav_file
-            .update_audio_for_data_channel_vec(&wave_format, &data_channel_vec)
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
             .unwrap();
-        wav_file.save_as(Path::new("./test_data_vec.wav")).unwrap();
-        PathBuf::from(&"./test_data_vec.wav")
+        let riff_buf = wav_file.to_bytes().unwrap();
+
+        // RF64 & BW64
+        for (container, magic) in [(WavContainer::Rf64, b"RF64"), (WavContainer::Bw64, b"BW64")] {
+            wav_file.container = container;
+            let file_buf = wav_file.to_bytes().unwrap();
+            assert_eq!(&file_buf[0x00..0x04], magic);
+            assert_eq!(file_buf[0x04..0x08], [0xff, 0xff, 0xff, 0xff]);
+            assert_eq!(file_buf[0x0c..0x10], [b'd', b's', b'6', b'4']);
+            // RIFF size, "data" chunk size and sample count
+            assert_eq!(file_buf[0x14..0x1c], (file_buf.len() as u64 - 8).to_le_bytes());
+            assert_eq!(file_buf[0x1c..0x24], 16u64.to_le_bytes());
+            assert_eq!(file_buf[0x24..0x2c], 4u64.to_le_bytes());
+            let rf64_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+            assert_eq!(rf64_wav_file.container, container);
+            assert_eq!(rf64_wav_file.sub_chunks, wav_file.sub_chunks);
+            assert_eq!(rf64_wav_file.to_bytes().unwrap(), file_buf);
+
+            let mut wav_writer =
+                WavWriter::new_with_container(std::io::Cursor::new(Vec::new()), &wave_format, container).unwrap();
+            wav_writer.write_audio_for_channel_data_vec(&channel_data_vec).unwrap();
+            assert_eq!(wav_writer.finalize().unwrap().into_inner(), file_buf);
+            let mut wav_reader = WavReader::new(std::io::Cursor::new(&file_buf)).unwrap();
+            assert_eq!(wav_reader.get_container(), container);
+            assert_eq!(wav_reader.get_frame_len(), 4);
+            assert_eq!(
+                wav_reader.read_audio_for_channel_data_vec(4).unwrap(),
+                wav_file.get_audio_for_channel_data_vec().unwrap().1
+            );
+        }
+
+        // RIFF or RF64 within 4GB
+        wav_file.container = WavContainer::RiffOrRf64;
+        assert_eq!(wav_file.to_bytes().unwrap(), riff_buf);
+        let mut wav_writer =
+            WavWriter::new_with_container(std::io::Cursor::new(Vec::new()), &wave_format, WavContainer::RiffOrRf64).unwrap();
+        wav_writer.write_audio_for_channel_data_vec(&channel_data_vec).unwrap();
+        assert_eq!(wav_writer.get_container(), WavContainer::Riff);
+        let file_buf = wav_writer.finalize().unwrap().into_inner();
+        assert_eq!(file_buf[0x0c..0x10], [b'J', b'U', b'N', b'K']);
+        let junk_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(junk_wav_file.container, WavContainer::Riff);
+        assert_eq!(
+            junk_wav_file.get_audio_for_channel_data_vec().unwrap(),
+            wav_file.get_audio_for_channel_data_vec().unwrap()
+        );
+
+        // "ds64" table
+        let mut file_buf: Vec<u8> = Vec::new();
+        file_buf.append(&mut b"RF64".to_vec());
+        file_buf.append(&mut [0xff, 0xff, 0xff, 0xff].to_vec());
+        file_buf.append(&mut b"WAVE".to_vec());
+        file_buf.append(&mut b"ds64".to_vec());
+        file_buf.append(&mut 40u32.to_le_bytes().to_vec());
+        file_buf.append(&mut 0u64.to_le_bytes().to_vec());
+        file_buf.append(&mut 16u64.to_le_bytes().to_vec());
+        file_buf.append(&mut 4u64.to_le_bytes().to_vec());
+        file_buf.append(&mut 1u32.to_le_bytes().to_vec());
+        file_buf.append(&mut b"abcd".to_vec());
+        file_buf.append(&mut 2u64.to_le_bytes().to_vec());
+        file_buf.append(&mut b"abcd".to_vec());
+        file_buf.append(&mut [0xff, 0xff, 0xff, 0xff].to_vec());
+        file_buf.append(&mut [0x01, 0x02].to_vec());
+        file_buf.append(&mut riff_buf[0x0c..].to_vec());
+        let riff_size = (file_buf.len() as u64 - 8).to_le_bytes();
+        file_buf[0x14..0x1c].copy_from_slice(&riff_size);
+        let mut table_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(table_wav_file.container, WavContainer::Rf64);
+        let chunk_idx = table_wav_file.get_sub_chunk_idx([b'a', b'b', b'c', b'd']).unwrap();
+        assert_eq!(table_wav_file.sub_chunks[chunk_idx].bytes_data_vec, vec![0x01, 0x02]);
+        assert_eq!(
+            table_wav_file.get_audio_for_channel_data_vec().unwrap(),
+            wav_file.get_audio_for_channel_data_vec().unwrap()
+        );
+
+        // RIFF size mismatch
+        file_buf[0x14] += 1;
+        assert!(WavFile::from_bytes(&file_buf).is_err());
     }
 
-    fn create_u8_vecs(
-        riff_chunk_size: &[u8],
-        fmt_chunk_format_id: &[u8],
-        fmt_chunk_channel: &[u8],
-        fmt_chunk_sampling_rate: &[u8],
-        fmt_chunk_bits: &[u8],
-        fmt_chunk_block_size: &[u8],
-        fmt_chunk_bit_rate: &[u8],
-        data_chunk_size: &[u8],
-        data_chunk_data: &[u8],
-    ) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
-        let riff_chunk_id: [u8; 4] = [0x52, 0x49, 0x46, 0x46];
-        let riff_chunk_wave: [u8; 4] = [0x57, 0x41, 0x56, 0x45];
-        let fmt_chunk_id: [u8; 4] = [0x66, 0x6D, 0x74, 0x20];
-        let fmt_chunk_size: [u8; 4] = [0x10, 0x00, 0x00, 0x00];
-        let data_chunk_id: [u8; 4] = [0x64, 0x61, 0x74, 0x61];
+    #[test]
+    fn odd_chunk_padding_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50]];
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 1,
+            sampling_rate: 8000,
+            bits: 8,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let odd_chunk_before = SubChunk {
+            chunk_id: [b'a', b'b', b'c', b'd'],
+            bytes_data_vec: vec![0x01, 0x02, 0x03],
+        };
+        let odd_chunk_after = SubChunk {
+            chunk_id: [b'e', b'f', b'g', b'h'],
+            bytes_data_vec: vec![0x04],
+        };
+        wav_file.sub_chunks.insert(0, odd_chunk_before.clone());
+        wav_file.sub_chunks.push(odd_chunk_after.clone());
 
-        let mut vec_of_fmt_chunk = fmt_chunk_format_id.to_vec();
-        vec_of_fmt_chunk.append(&mut fmt_chunk_channel.to_vec());
-        vec_of_fmt_chunk.append(&mut fmt_chunk_sampling_rate.to_vec());
-        vec_of_fmt_chunk.append(&mut fmt_chunk_bits.to_vec());
-        vec_of_fmt_chunk.append(&mut fmt_chunk_block_size.to_vec());
-        vec_of_fmt_chunk.append(&mut fmt_chunk_bit_rate.to_vec());
+        // Pad bytes are written after the odd-length bodies and included in RIFF size.
+        let file_buf = wav_file.to_bytes().unwrap();
+        #[rustfmt::skip]
+        let expected_head: Vec<u8> = vec![
+            b'R', b'I', b'F', b'F', 0x3E, 0x00, 0x00, 0x00, b'W', b'A', b'V', b'E',
+            b'a', b'b', b'c', b'd', 0x03, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x00,
+            b'f', b'm', b't', b' ',
+        ];
+        #[rustfmt::skip]
+        let expected_tail: Vec<u8> = vec![
+            b'd', b'a', b't', b'a', 0x03, 0x00, 0x00, 0x00, 0x80, 0xC0, 0x40, 0x00,
+            b'e', b'f', b'g', b'h', 0x01, 0x00, 0x00, 0x00, 0x04, 0x00,
+        ];
+        assert_eq!(file_buf.len(), 0x46);
+        assert_eq!(file_buf[0..expected_head.len()], expected_head);
+        assert_eq!(file_buf[file_buf.len() - expected_tail.len()..], expected_tail);
 
-        let vec_of_data_chunk = data_chunk_data.to_vec();
+        // Pad bytes are skipped when reading.
+        let read_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(read_wav_file.sub_chunks, wav_file.sub_chunks);
+        assert_eq!(read_wav_file.to_bytes().unwrap(), file_buf);
+        let mut wav_reader = WavReader::new(std::io::Cursor::new(&file_buf)).unwrap();
+        assert_eq!(wav_reader.get_frame_len(), 3);
+        assert_eq!(
+            wav_reader.get_sub_chunks().to_vec(),
+            vec![odd_chunk_before, wav_file.sub_chunks[1].clone(), odd_chunk_after]
+        );
+        assert_eq!(
+            wav_reader.read_audio_for_channel_data_vec(3).unwrap(),
+            wav_file.get_audio_for_channel_data_vec().unwrap().1
+        );
 
-        let mut vec_of_file = riff_chunk_id.to_vec();
-        vec_of_file.append(&mut riff_chunk_size.to_vec());
-        vec_of_file.append(&mut riff_chunk_wave.to_vec());
-        vec_of_file.append(&mut fmt_chunk_id.to_vec());
-        vec_of_file.append(&mut fmt_chunk_size.to_vec());
-        vec_of_file.append(&mut vec_of_fmt_chunk.clone());
-        vec_of_file.append(&mut data_chunk_id.to_vec());
-        vec_of_file.append(&mut data_chunk_size.to_vec());
-        vec_of_file.append(&mut vec_of_data_chunk.clone());
+        // The missing pad byte of the last chunk is tolerated.
+        let mut unpadded_buf = file_buf[0..file_buf.len() - 1].to_vec();
+        unpadded_buf[0x04] -= 1;
+        assert_eq!(WavFile::from_bytes(&unpadded_buf).unwrap().sub_chunks, wav_file.sub_chunks);
+
+        // WavWriter pads the odd-length "data" chunk.
+        let mut wav_writer = WavWriter::new(std::io::Cursor::new(Vec::new()), &wave_format).unwrap();
+        wav_writer.write_audio_for_channel_data_vec(&channel_data_vec).unwrap();
+        let written_buf = wav_writer.finalize().unwrap().into_inner();
+        wav_file.sub_chunks.remove(0);
+        wav_file.sub_chunks.pop();
+        assert_eq!(written_buf, wav_file.to_bytes().unwrap());
+        assert_eq!(written_buf.len() % 2, 0);
+    }
+
+    #[test]
+    fn lenient_open_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50, 0.25], vec![0.25, -0.25, 0.00, 0.50]];
+        let path_buf = create_test_file(1, 2, 8000, 16, &channel_data_vec);
+        let mut file_buf = Vec::new();
+        File::open(&path_buf).unwrap().read_to_end(&mut file_buf).unwrap();
+        let wav_file = WavFile::open(&path_buf).unwrap();
+        let (channel_wave_format, expected_channel_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+
+        // Valid file
+        let (lenient_wav_file, warnings) = WavFile::open_lenient(&path_buf).unwrap();
+        remove_file(&path_buf).unwrap();
+        assert_eq!(lenient_wav_file, wav_file);
+        assert_eq!(warnings, vec![]);
+
+        // 0 RIFF size and 0 "data" chunk size
+        let mut zero_size_buf = file_buf.clone();
+        zero_size_buf[0x04..0x08].copy_from_slice(&[0x00; 4]);
+        zero_size_buf[0x28..0x2c].copy_from_slice(&[0x00; 4]);
+        assert!(WavFile::from_bytes(&zero_size_buf).is_err());
+        let (lenient_wav_file, warnings) = WavFile::from_bytes_lenient(&zero_size_buf).unwrap();
+        assert_eq!(lenient_wav_file.sub_chunks, wav_file.sub_chunks);
+        assert_eq!(
+            warnings,
+            vec![
+                WavRepairWarning::RiffSizeMismatch {
+                    declared: 0,
+                    actual: 0x34
+                },
+                WavRepairWarning::ChunkSizePlaceholder {
+                    chunk_id: [b'd', b'a', b't', b'a'],
+                    declared: 0,
+                    actual: 16
+                },
+            ]
+        );
+
+        // 0xFFFFFFFF "data" chunk size
+        let mut placeholder_buf = file_buf.clone();
+        placeholder_buf[0x28..0x2c].copy_from_slice(&[0xff; 4]);
+        assert!(WavFile::from_bytes(&placeholder_buf).is_err());
+        let (lenient_wav_file, warnings) = WavFile::from_bytes_lenient(&placeholder_buf).unwrap();
+        assert_eq!(lenient_wav_file.sub_chunks, wav_file.sub_chunks);
+        assert_eq!(
+            warnings,
+            vec![WavRepairWarning::ChunkSizePlaceholder {
+                chunk_id: [b'd', b'a', b't', b'a'],
+                declared: 0xffffffff,
+                actual: 16
+            }]
+        );
+
+        // Truncated "data" chunk
+        let truncated_buf = file_buf[0..file_buf.len() - 3].to_vec();
+        assert!(WavFile::from_bytes(&truncated_buf).is_err());
+        let (lenient_wav_file, warnings) = WavFile::from_bytes_lenient(&truncated_buf).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                WavRepairWarning::RiffSizeMismatch {
+                    declared: 0x34,
+                    actual: 0x31
+                },
+                WavRepairWarning::TruncatedChunk {
+                    chunk_id: [b'd', b'a', b't', b'a'],
+                    declared: 16,
+                    actual: 13
+                },
+                WavRepairWarning::PartialBlock { size: 1 },
+            ]
+        );
+        let (wave_format, channel_vec) = lenient_wav_file.get_audio_for_channel_data_vec().unwrap();
+        assert_eq!(wave_format, channel_wave_format);
+        assert_eq!(
+            channel_vec,
+            expected_channel_vec
+                .iter()
+                .map(|data_vec| data_vec[0..3].to_vec())
+                .collect::<Vec<Vec<f64>>>()
+        );
+
+        // Trailing bytes
+        for trailing_bytes in [vec![0xff; 10], vec![b'a'; 5]] {
+            let mut trailing_buf = file_buf.clone();
+            trailing_buf.append(&mut trailing_bytes.clone());
+            assert!(WavFile::from_bytes(&trailing_buf).is_err());
+            let (lenient_wav_file, warnings) = WavFile::from_bytes_lenient(&trailing_buf).unwrap();
+            assert_eq!(lenient_wav_file.sub_chunks, wav_file.sub_chunks);
+            assert_eq!(
+                warnings,
+                vec![
+                    WavRepairWarning::RiffSizeMismatch {
+                        declared: 0x34,
+                        actual: 0x34 + trailing_bytes.len() as u64
+                    },
+                    WavRepairWarning::TrailingBytes {
+                        offset: 0x3c,
+                        size: trailing_bytes.len() as u64
+                    },
+                ]
+            );
+        }
+
+        // Not wav data
+        assert!(WavFile::from_bytes_lenient(&file_buf[0..8]).is_err());
+
+        // Truncated ADPCM "data" chunk (block align 256, 505 samples per block)
+        let adpcm_format = WaveFormat {
+            id: WAVEFORMAT_ID_IMA_ADPCM,
+            channel: 1,
+            sampling_rate: 8000,
+            bits: 4,
+        };
+        let mut adpcm_wav_file = WavFile::new();
+        adpcm_wav_file
+            .update_audio_for_channel_data_vec(&adpcm_format, &vec![vec![0.25; 1200]])
+            .unwrap();
+        let adpcm_buf = adpcm_wav_file.to_bytes().unwrap();
+        let (lenient_wav_file, warnings) = WavFile::from_bytes_lenient(&adpcm_buf).unwrap();
+        assert_eq!(lenient_wav_file, adpcm_wav_file);
+        assert_eq!(warnings, vec![]);
+        let (lenient_wav_file, warnings) = WavFile::from_bytes_lenient(&adpcm_buf[0..adpcm_buf.len() - 100]).unwrap();
+        assert_eq!(warnings.last(), Some(&WavRepairWarning::PartialBlock { size: 156 }));
+        let data_chunk = lenient_wav_file.sub_chunks.last().unwrap();
+        assert_eq!(data_chunk.bytes_data_vec.len(), 512);
+        assert_eq!(lenient_wav_file.get_fact().unwrap(), Some(FactChunk::new(1010)));
+        let (_, channel_vec) = lenient_wav_file.get_audio_for_channel_data_vec().unwrap();
+        assert_eq!(channel_vec[0].len(), 1010);
+    }
+
+    #[test]
+    fn error_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50, 0.25], vec![0.25, -0.25, 0.00, 0.50]];
+        let path_buf = create_test_file(1, 2, 8000, 16, &channel_data_vec);
+        let mut file_buf = Vec::new();
+        File::open(&path_buf).unwrap().read_to_end(&mut file_buf).unwrap();
+        remove_file(&path_buf).unwrap();
+
+        // Format error with chunk id and offset
+        let mut wrong_size_buf = file_buf.clone();
+        wrong_size_buf[0x28] += 1;
+        match WavFile::from_bytes(&wrong_size_buf) {
+            Err(WavF64VecError::Format {
+                err_kind: WavF64VecErrorKind::SubChunkSizeError,
+                op_chunk_id: Some([b'd', b'a', b't', b'a']),
+                op_offset: Some(0x24),
+                ..
+            }) => {}
+            _ => panic!(),
+        }
+        let mut wrong_format_buf = file_buf.clone();
+        wrong_format_buf[0x14] = 0x99;
+        let wav_file: super::super::Result<WavFile> = WavFile::from_bytes(&wrong_format_buf);
+        match wav_file.unwrap().get_format() {
+            Err(WavF64VecError::Format {
+                op_chunk_id: Some([b'f', b'm', b't', b' ']),
+                op_offset: None,
+                ..
+            }) => {}
+            _ => panic!(),
+        }
+        match WavReader::new(std::io::Cursor::new(&wrong_format_buf)) {
+            Err(WavF64VecError::Format {
+                op_chunk_id: Some([b'f', b'm', b't', b' ']),
+                op_offset: Some(0x0c),
+                ..
+            }) => {}
+            _ => panic!(),
+        }
+        let mut zero_channel_buf = file_buf.clone();
+        zero_channel_buf[0x16] = 0x00;
+        match WavReader::new(std::io::Cursor::new(&zero_channel_buf)) {
+            Err(WavF64VecError::Format {
+                op_chunk_id: Some([b'f', b'm', b't', b' ']),
+                op_offset: Some(0x0c),
+                ..
+            }) => {}
+            _ => panic!(),
+        }
+        let err = WavFile::open(&path_buf).unwrap_err();
+        assert_eq!(err.err_kind(), Some(&WavF64VecErrorKind::PathIsNotFile));
+        assert!(std::error::Error::source(&err).is_none());
+
+        // I/O error is preserved as the source.
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 8000,
+            bits: 16,
+        };
+        let err = WavWriter::create(Path::new("./no_such_dir/test.wav"), &wave_format)
+            .err()
+            .unwrap();
+        assert_eq!(err.err_kind(), None);
+        match &err {
+            WavF64VecError::Io(io_err) => assert_eq!(io_err.kind(), std::io::ErrorKind::NotFound),
+            _ => panic!(),
+        }
+        assert!(std::error::Error::source(&err)
+            .unwrap()
+            .downcast_ref::<std::io::Error>()
+            .is_some());
+    }
+
+    #[test]
+    fn band_limited_resample_test() {
+        let sine = |freq: f64, sampling_rate: usize, len: usize| -> Vec<f64> {
+            (0..len)
+                .map(|idx| 0.5 * (2.0 * std::f64::consts::PI * freq * idx as f64 / sampling_rate as f64).sin())
+                .collect()
+        };
+        // Maximum error in the middle. (The edges fade because the outside of the signal is zero.)
+        let max_diff = |data_vec: &Vec<f64>, expected_vec: &Vec<f64>, margin: usize| -> f64 {
+            data_vec[margin..data_vec.len() - margin]
+                .iter()
+                .zip(&expected_vec[margin..expected_vec.len() - margin])
+                .map(|(data, expected)| (data - expected).abs())
+                .fold(0.0, f64::max)
+        };
+
+        for quality in [ResampleQuality::Fast, ResampleQuality::Medium, ResampleQuality::Best] {
+            let options = ResampleOptions::new(quality);
+            let tolerance = 10f64.powf(-options.stopband_attenuation / 20.0) * 10.0;
+
+            // Downsampling: the passband tone is kept and the tone above the new Nyquist frequency is removed.
+            let src_channel_data_vec = vec![sine(1000.0, 48000, 4800), sine(12000.0, 48000, 4800)];
+            let dst_channel_data_vec = resample_for_channel_data_vec(&src_channel_data_vec, 48000, 16000, &options).unwrap();
+            assert_eq!(dst_channel_data_vec[0].len(), 1600);
+            assert!(max_diff(&dst_channel_data_vec[0], &sine(1000.0, 16000, 1600), 400) < tolerance);
+            assert!(max_diff(&dst_channel_data_vec[1], &vec![0.0; 1600], 400) < tolerance);
+
+            // Upsampling with a non-integer ratio
+            let src_channel_data_vec = vec![sine(1000.0, 44100, 4410)];
+            let dst_channel_data_vec = resample_for_channel_data_vec(&src_channel_data_vec, 44100, 48000, &options).unwrap();
+            assert_eq!(dst_channel_data_vec[0].len(), 4800);
+            assert!(max_diff(&dst_channel_data_vec[0], &sine(1000.0, 48000, 4800), 1200) < tolerance);
+
+            // data channel vec
+            let src_data_channel_vec: Vec<Vec<f64>> = (0..4410).map(|idx| vec![src_channel_data_vec[0][idx]; 2]).collect();
+            let dst_data_channel_vec = resample_for_data_channel_vec(&src_data_channel_vec, 44100, 48000, &options).unwrap();
+            assert_eq!(dst_data_channel_vec.len(), 4800);
+            for (data_idx, channel_vec) in dst_data_channel_vec.iter().enumerate() {
+                assert_eq!(channel_vec, &vec![dst_channel_data_vec[0][data_idx]; 2]);
+            }
+        }
+
+        // Same length as the linear interpolation
+        let src_channel_data_vec = vec![vec![0.25; 1001]];
+        let options = ResampleOptions::new(ResampleQuality::Fast);
+        assert_eq!(
+            resample_for_channel_data_vec(&src_channel_data_vec, 48000, 22050, &options).unwrap()[0].len(),
+            convert_sampling_rate_for_channel_data_vec(&src_channel_data_vec, 48000, 22050).unwrap()[0].len()
+        );
+        assert_eq!(
+            resample_for_channel_data_vec(&src_channel_data_vec, 48000, 48000, &options).unwrap(),
+            src_channel_data_vec
+        );
+
+        // Out of range
+        let wrong_options = ResampleOptions {
+            passband: 1.0,
+            stopband_attenuation: 100.0,
+        };
+        assert!(resample_for_channel_data_vec(&src_channel_data_vec, 48000, 16000, &wrong_options).is_err());
+        assert!(resample_for_channel_data_vec(&src_channel_data_vec, 48000, 0, &options).is_err());
+    }
+
+    #[test]
+    fn streaming_resample_test() {
+        let src_channel_data_vec: Vec<Vec<f64>> = (0..2)
+            .map(|channel_idx| {
+                (0..3000)
+                    .map(|data_idx| ((data_idx * (channel_idx + 3)) as f64 * 0.01).sin() * 0.5)
+                    .collect()
+            })
+            .collect();
+        let options = ResampleOptions::new(ResampleQuality::Medium);
+        let block_len_vec = [1, 7, 0, 100, 333, 1, 1024];
+
+        for (src_sampling_rate, dst_sampling_rate) in [(48000, 16000), (44100, 48000), (8000, 8000)] {
+            let expected_channel_data_vec =
+                resample_for_channel_data_vec(&src_channel_data_vec, src_sampling_rate, dst_sampling_rate, &options).unwrap();
+
+            // channel data vec by blocks of various sizes
+            let mut resampler = Resampler::new(2, src_sampling_rate, dst_sampling_rate, &options).unwrap();
+            let mut dst_channel_data_vec: Vec<Vec<f64>> = vec![Vec::new(); 2];
+            let mut data_idx = 0;
+            for block_len in block_len_vec.iter().cycle() {
+                if data_idx >= 3000 {
+                    break;
+                }
+                let end_idx = (data_idx + block_len).min(3000);
+                let block_channel_data_vec: Vec<Vec<f64>> = src_channel_data_vec
+                    .iter()
+                    .map(|data_vec| data_vec[data_idx..end_idx].to_vec())
+                    .collect();
+                for (dst_data_vec, mut block_data_vec) in dst_channel_data_vec
+                    .iter_mut()
+                    .zip(resampler.process_for_channel_data_vec(&block_channel_data_vec).unwrap())
+                {
+                    dst_data_vec.append(&mut block_data_vec);
+                }
+                data_idx = end_idx;
+            }
+            for (dst_data_vec, mut rest_data_vec) in
+                dst_channel_data_vec.iter_mut().zip(resampler.finalize_for_channel_data_vec())
+            {
+                dst_data_vec.append(&mut rest_data_vec);
+            }
+            assert_eq!(dst_channel_data_vec, expected_channel_data_vec);
+
+            // data channel vec
+            let src_data_channel_vec: Vec<Vec<f64>> = (0..3000)
+                .map(|data_idx| vec![src_channel_data_vec[0][data_idx], src_channel_data_vec[1][data_idx]])
+                .collect();
+            let mut resampler = Resampler::new(2, src_sampling_rate, dst_sampling_rate, &options).unwrap();
+            let mut dst_data_channel_vec: Vec<Vec<f64>> = Vec::new();
+            for block_data_channel_vec in src_data_channel_vec.chunks(250) {
+                dst_data_channel_vec.append(
+                    &mut resampler
+                        .process_for_data_channel_vec(&block_data_channel_vec.to_vec())
+                        .unwrap(),
+                );
+            }
+            dst_data_channel_vec.append(&mut resampler.finalize_for_data_channel_vec());
+            assert_eq!(
+                dst_data_channel_vec,
+                resample_for_data_channel_vec(&src_data_channel_vec, src_sampling_rate, dst_sampling_rate, &options).unwrap()
+            );
+        }
+
+        // Channel mismatch
+        let mut resampler = Resampler::new(2, 48000, 16000, &options).unwrap();
+        assert!(resampler.process_for_channel_data_vec(&vec![vec![0.0; 10]]).is_err());
+        assert!(Resampler::new(0, 48000, 16000, &options).is_err());
+    }
+
+    #[test]
+    fn dither_test() {
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 44100,
+            bits: 16,
+        };
+        let lsb = 1.0 / BIT16_WAVE_DIVISOR as f64;
+        // Quiet fade below 1 LSB
+        let channel_data_vec: Vec<Vec<f64>> = (0..2)
+            .map(|_| {
+                (0..10000)
+                    .map(|idx| 0.3 * lsb * (1.0 - idx as f64 / 10000.0) * (idx as f64 * 0.05).sin())
+                    .collect()
+            })
+            .collect();
+        let data_channel_vec: Vec<Vec<f64>> = (0..10000)
+            .map(|idx| vec![channel_data_vec[0][idx], channel_data_vec[1][idx]])
+            .collect();
+        let encode = |options: &EncodeOptions| -> Vec<Vec<f64>> {
+            let mut wav_file = WavFile::new();
+            wav_file
+                .update_audio_for_channel_data_vec_with_options(&wave_format, &channel_data_vec, options)
+                .unwrap();
+            let mut data_wav_file = WavFile::new();
+            data_wav_file
+                .update_audio_for_data_channel_vec_with_options(&wave_format, &data_channel_vec, options)
+                .unwrap();
+            assert_eq!(data_wav_file, wav_file);
+            wav_file.get_audio_for_channel_data_vec().unwrap().1
+        };
+
+        // Default options are the same as without options.
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let rounded_channel_data_vec = encode(&EncodeOptions::default());
+        assert_eq!(rounded_channel_data_vec, wav_file.get_audio_for_channel_data_vec().unwrap().1);
+        assert!(rounded_channel_data_vec[0].iter().all(|val| *val == 0.0));
+
+        for (dither, max_error) in [(Dither::Rectangular, 1.0), (Dither::Triangular, 1.5)] {
+            let options = EncodeOptions {
+                dither,
+                noise_shaping: NoiseShaping::None,
+                seed: 1,
+                ..Default::default()
+            };
+            let dithered_channel_data_vec = encode(&options);
+            // The fade is not truncated to silence.
+            assert!(dithered_channel_data_vec[0].iter().any(|val| *val != 0.0));
+            for (dithered_data_vec, data_vec) in dithered_channel_data_vec.iter().zip(&channel_data_vec) {
+                for (dithered_val, val) in dithered_data_vec.iter().zip(data_vec) {
+                    assert!((dithered_val - val).abs() <= (max_error + 1e-9) * lsb);
+                }
+            }
+            // Reproducible with the same seed
+            assert_eq!(encode(&options), dithered_channel_data_vec);
+            assert_ne!(encode(&EncodeOptions { seed: 2, ..options }), dithered_channel_data_vec);
+        }
+
+        // The first order noise shaping keeps the accumulated error (low frequency error) bounded.
+        let accumulated_error = |noise_shaping: NoiseShaping| -> f64 {
+            let encoded_channel_data_vec = encode(&EncodeOptions {
+                dither: Dither::Rectangular,
+                noise_shaping,
+                seed: 3,
+                ..Default::default()
+            });
+            encoded_channel_data_vec[0]
+                .iter()
+                .zip(&channel_data_vec[0])
+                .map(|(encoded_val, val)| (encoded_val - val) / lsb)
+                .sum::<f64>()
+                .abs()
+        };
+        assert!(accumulated_error(NoiseShaping::FirstOrder) <= 1.0 + 1e-6);
+        assert!(accumulated_error(NoiseShaping::SecondOrder) <= 10.0);
+        assert!(accumulated_error(NoiseShaping::Lipshitz).is_finite());
+
+        // Float formats are not affected.
+        let float_wave_format = WaveFormat {
+            id: 3,
+            channel: 2,
+            sampling_rate: 44100,
+            bits: 32,
+        };
+        let mut float_wav_file = WavFile::new();
+        float_wav_file
+            .update_audio_for_channel_data_vec_with_options(
+                &float_wave_format,
+                &channel_data_vec,
+                &EncodeOptions {
+                    dither: Dither::Triangular,
+                    noise_shaping: NoiseShaping::SecondOrder,
+                    seed: 4,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        wav_file
+            .update_audio_for_channel_data_vec(&float_wave_format, &channel_data_vec)
+            .unwrap();
+        assert_eq!(float_wav_file, wav_file);
+    }
+
+    #[test]
+    fn pcm_scaling_test() {
+        let scaling_vec = [PcmScaling::Symmetric, PcmScaling::Asymmetric, PcmScaling::Custom(1000.5)];
+        for bits in [8, 16, 24, 32] {
+            let min_val: i64 = -(1 << (bits - 1));
+            let max_val: i64 = (1 << (bits - 1)) - 1;
+            // All values of 8bit and 16bit, and every 251st value and both ends of 24bit and 32bit.
+            let step = if bits <= 16 { 1 } else { (1 << (bits - 16)) * 251 + 1 };
+            let mut pcm_val_vec: Vec<i64> = (min_val..=max_val).step_by(step).collect();
+            pcm_val_vec.append(&mut vec![min_val + 1, -1, 0, 1, max_val - 1, max_val]);
+
+            for scaling in scaling_vec {
+                // Bit-exact round trip from bytes to f64 and back
+                let mut bytes_data_vec: Vec<u8> = Vec::new();
+                for pcm_val in &pcm_val_vec {
+                    let bytes = if bits == 8 {
+                        vec![(pcm_val + 128) as u8]
+                    } else {
+                        pcm_val.to_le_bytes()[0..bits / 8].to_vec()
+                    };
+                    let f64_val = bytes_to_f64wave_with_scaling(1, &bytes, &scaling).unwrap();
+                    assert_eq!(f64wave_to_bytes_with_scaling(1, f64_val, bits, &scaling).unwrap(), bytes);
+                    bytes_data_vec.append(&mut bytes.clone());
+                }
+
+                // Round trip through WavFile
+                let wave_format = WaveFormat {
+                    id: 1,
+                    channel: 1,
+                    sampling_rate: 48000,
+                    bits,
+                };
+                let mut wav_file = WavFile::new();
+                wav_file
+                    .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.0; pcm_val_vec.len()]])
+                    .unwrap();
+                let data_chunk_idx = wav_file.get_sub_chunk_idx([b'd', b'a', b't', b'a']).unwrap();
+                wav_file.sub_chunks[data_chunk_idx].bytes_data_vec = bytes_data_vec.clone();
+                let (_, channel_data_vec) = wav_file
+                    .get_audio_for_channel_data_vec_with_options(&DecodeOptions { scaling })
+                    .unwrap();
+                let (_, data_channel_vec) = wav_file
+                    .get_audio_for_data_channel_vec_with_options(&DecodeOptions { scaling })
+                    .unwrap();
+                assert_eq!(data_channel_vec.concat(), channel_data_vec[0]);
+                wav_file
+                    .update_audio_for_channel_data_vec_with_options(
+                        &wave_format,
+                        &channel_data_vec,
+                        &EncodeOptions {
+                            scaling,
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap();
+                assert_eq!(wav_file.sub_chunks[data_chunk_idx].bytes_data_vec, bytes_data_vec);
+            }
+
+            // Full scale of each convention
+            let to_pcm_val = |f64_val: f64, scaling: &PcmScaling| -> i64 {
+                let bytes = f64wave_to_bytes_with_scaling(1, f64_val, bits, scaling).unwrap();
+                if bits == 8 {
+                    bytes[0] as i64 - 128
+                } else {
+                    let mut buffer = [0u8; 8];
+                    buffer[8 - bits / 8..].copy_from_slice(&bytes);
+                    i64::from_le_bytes(buffer) >> (64 - bits)
+                }
+            };
+            assert_eq!(to_pcm_val(1.0, &PcmScaling::Symmetric), max_val);
+            assert_eq!(to_pcm_val(-1.0, &PcmScaling::Symmetric), -max_val);
+            assert_eq!(to_pcm_val(1.0, &PcmScaling::Asymmetric), max_val);
+            assert_eq!(to_pcm_val(-1.0, &PcmScaling::Asymmetric), min_val);
+            assert_eq!(to_pcm_val(0.5, &PcmScaling::Custom(100.0)), 50);
+            assert_eq!(to_pcm_val(2.0, &PcmScaling::Symmetric), max_val);
+            assert_eq!(to_pcm_val(-2.0, &PcmScaling::Symmetric), min_val);
+        }
+
+        // 24bit is rounded to nearest, not truncated.
+        let f64_val = (0x123456 as f64 + 0.6) / BIT24_WAVE_DIVISOR as f64;
+        assert_eq!(f64wave_to_bytes(1, f64_val, 24).unwrap(), vec![0x57, 0x34, 0x12]);
+        let f64_val = -(0x123456 as f64 + 0.4) / BIT24_WAVE_DIVISOR as f64;
+        assert_eq!(
+            f64wave_to_bytes(1, f64_val, 24).unwrap(),
+            (-0x123456i32).to_le_bytes()[0..3].to_vec()
+        );
+
+        // Wrong full scale
+        assert!(f64wave_to_bytes_with_scaling(1, 0.5, 16, &PcmScaling::Custom(0.0)).is_err());
+        assert!(bytes_to_f64wave_with_scaling(1, &[0, 0], &PcmScaling::Custom(f64::NAN)).is_err());
+    }
+
+    #[test]
+    fn g711_test() {
+        let to_pcm_val = |format_id: usize, code: u8| -> i64 {
+            (bytes_to_f64wave(format_id, &[code]).unwrap() * BIT16_WAVE_DIVISOR as f64) as i64
+        };
+        // Known codes
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_ALAW, 0xd5), 8);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_ALAW, 0x55), -8);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_ALAW, 0xaa), 32256);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_ALAW, 0x2a), -32256);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_MULAW, 0xff), 0);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_MULAW, 0x7f), 0);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_MULAW, 0x80), 32124);
+        assert_eq!(to_pcm_val(WAVEFORMAT_ID_MULAW, 0x00), -32124);
+
+        for format_id in [WAVEFORMAT_ID_ALAW, WAVEFORMAT_ID_MULAW] {
+            // All codes are decoded and encoded back. (mu-law has -0 and +0.)
+            for code in 0..=0xffu8 {
+                let f64_val = bytes_to_f64wave(format_id, &[code]).unwrap();
+                let expected_code = if format_id == WAVEFORMAT_ID_MULAW && code == 0x7f {
+                    0xff
+                } else {
+                    code
+                };
+                assert_eq!(f64wave_to_bytes(format_id, f64_val, 8).unwrap(), vec![expected_code]);
+            }
+            // Encoding error is within the half step of the segment.
+            for pcm_val in (i16::MIN..=i16::MAX).step_by(7) {
+                let f64_val = pcm_val as f64 / BIT16_WAVE_DIVISOR as f64;
+                let code = f64wave_to_bytes(format_id, f64_val, 8).unwrap()[0];
+                let error = (to_pcm_val(format_id, code) - pcm_val as i64).abs();
+                assert!(error <= (pcm_val as i64).abs() / 16 + 16);
+            }
+        }
+
+        // Through WavFile
+        let wave_format = WaveFormat {
+            id: WAVEFORMAT_ID_MULAW,
+            channel: 2,
+            sampling_rate: 8000,
+            bits: 8,
+        };
+        let channel_data_vec: Vec<Vec<f64>> = (0..2)
+            .map(|channel_idx| {
+                (0..800)
+                    .map(|idx| 0.5 * (2.0 * std::f64::consts::PI * 440.0 * (idx + channel_idx) as f64 / 8000.0).sin())
+                    .collect()
+            })
+            .collect();
+        for format_id in [WAVEFORMAT_ID_ALAW, WAVEFORMAT_ID_MULAW] {
+            let wave_format = WaveFormat {
+                id: format_id,
+                ..wave_format
+            };
+            let mut wav_file = WavFile::new();
+            wav_file
+                .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+                .unwrap();
+            // "fmt" chunk of non-PCM format has the extension size 0.
+            let fmt_chunk_idx = wav_file.get_sub_chunk_idx([b'f', b'm', b't', b' ']).unwrap();
+            assert_eq!(wav_file.sub_chunks[fmt_chunk_idx].bytes_data_vec.len(), 18);
+            assert_eq!(wav_file.sub_chunks[fmt_chunk_idx].bytes_data_vec[0x10..0x12], [0, 0]);
+            let data_chunk_idx = wav_file.get_sub_chunk_idx([b'd', b'a', b't', b'a']).unwrap();
+            assert_eq!(wav_file.sub_chunks[data_chunk_idx].bytes_data_vec.len(), 1600);
+
+            let (read_wave_format, read_channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+            assert_eq!(read_wave_format, wave_format);
+            for (read_data_vec, data_vec) in read_channel_data_vec.iter().zip(&channel_data_vec) {
+                for (read_val, val) in read_data_vec.iter().zip(data_vec) {
+                    assert!((read_val - val).abs() <= val.abs() / 16.0 + 16.0 / BIT16_WAVE_DIVISOR as f64);
+                }
+            }
+            let (_, read_data_channel_vec) = wav_file.get_audio_for_data_channel_vec().unwrap();
+            assert_eq!(
+                read_data_channel_vec[1],
+                vec![read_channel_data_vec[0][1], read_channel_data_vec[1][1]]
+            );
+
+            // Decoded data is encoded to the same codes.
+            let mut copied_wav_file = WavFile::new();
+            copied_wav_file
+                .update_audio_for_data_channel_vec(&wave_format, &read_data_channel_vec)
+                .unwrap();
+            assert_eq!(copied_wav_file, wav_file);
+
+            let mut file_buf: Vec<u8> = Vec::new();
+            wav_file.write_to(&mut file_buf).unwrap();
+            assert_eq!(WavFile::from_bytes(&file_buf).unwrap(), wav_file);
+        }
+
+        // A-law and mu-law are only 8bit.
+        let mut wav_file = WavFile::new();
+        assert!(wav_file
+            .update_audio_for_channel_data_vec(
+                &WaveFormat {
+                    id: WAVEFORMAT_ID_ALAW,
+                    bits: 16,
+                    ..wave_format
+                },
+                &channel_data_vec
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn ima_adpcm_test() {
+        // mono, 8000Hz, 8 bytes per block, 9 samples per block
+        let mut format_chunk_body: Vec<u8> = vec![0x11, 0x00, 0x01, 0x00, 0x40, 0x1f, 0x00, 0x00];
+        format_chunk_body.append(&mut vec![
+            0xc7, 0x1b, 0x00, 0x00, 0x08, 0x00, 0x04, 0x00, 0x02, 0x00, 0x09, 0x00,
+        ]);
+        let data_chunk_body: Vec<u8> = vec![
+            // header: 1000, step index 0
+            0xe8, 0x03, 0x00, 0x00, // codes: 4, 0, 0, 0, 0, 0, 0, 0
+            0x04, 0x00, 0x00, 0x00, // short last block: 16, step index 5
+            0x10, 0x00, 0x05, 0x00,
+        ];
+        let mut wav_file = WavFile::new();
+        for (chunk_id, bytes_data_vec) in [
+            ([b'f', b'm', b't', b' '], format_chunk_body),
+            ([b'f', b'a', b'c', b't'], vec![0x0a, 0x00, 0x00, 0x00]),
+            ([b'd', b'a', b't', b'a'], data_chunk_body),
+        ] {
+            wav_file
+                .update_sub_chunk(SubChunk {
+                    chunk_id,
+                    bytes_data_vec,
+                })
+                .unwrap();
+        }
+        let (wave_format, channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+        assert_eq!(
+            wave_format,
+            WaveFormat {
+                id: WAVEFORMAT_ID_IMA_ADPCM,
+                channel: 1,
+                sampling_rate: 8000,
+                bits: 4,
+            }
+        );
+        let pcm_vec: Vec<i64> = channel_data_vec[0]
+            .iter()
+            .map(|val| (val * BIT16_WAVE_DIVISOR as f64) as i64)
+            .collect();
+        assert_eq!(pcm_vec, vec![1000, 1007, 1008, 1009, 1009, 1009, 1009, 1009, 1009, 16]);
+        // The number of samples is limited by "fact" chunk.
+        let fact_chunk_idx = wav_file.get_sub_chunk_idx([b'f', b'a', b'c', b't']).unwrap();
+        wav_file.sub_chunks[fact_chunk_idx].bytes_data_vec = vec![0x05, 0x00, 0x00, 0x00];
+        assert_eq!(wav_file.get_audio_for_data_channel_vec().unwrap().1.len(), 5);
+
+        // Encode
+        let wave_format = WaveFormat {
+            id: WAVEFORMAT_ID_IMA_ADPCM,
+            channel: 2,
+            sampling_rate: 22050,
+            bits: 4,
+        };
+        let channel_data_vec: Vec<Vec<f64>> = (0..2)
+            .map(|channel_idx| {
+                (0..1000)
+                    .map(|idx| 0.5 * (2.0 * std::f64::consts::PI * 440.0 * (idx + channel_idx * 10) as f64 / 22050.0).sin())
+                    .collect()
+            })
+            .collect();
+        let data_channel_vec: Vec<Vec<f64>> = (0..1000)
+            .map(|idx| vec![channel_data_vec[0][idx], channel_data_vec[1][idx]])
+            .collect();
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let mut data_wav_file = WavFile::new();
+        data_wav_file
+            .update_audio_for_data_channel_vec(&wave_format, &data_channel_vec)
+            .unwrap();
+        assert_eq!(data_wav_file, wav_file);
+
+        // "fmt" chunk: 1024 bytes per block, 1017 samples per block
+        assert_eq!(
+            wav_file.sub_chunks[0],
+            SubChunk {
+                chunk_id: [b'f', b'm', b't', b' '],
+                bytes_data_vec: vec![
+                    0x11, 0x00, 0x02, 0x00, 0x22, 0x56, 0x00, 0x00, 0xb9, 0x56, 0x00, 0x00, 0x00, 0x04, 0x04, 0x00, 0x02, 0x00,
+                    0xf9, 0x03
+                ],
+            }
+        );
+        // "fact" chunk is before "data" chunk.
+        assert_eq!(
+            wav_file.sub_chunks[1],
+            SubChunk {
+                chunk_id: [b'f', b'a', b'c', b't'],
+                bytes_data_vec: vec![0xe8, 0x03, 0x00, 0x00],
+            }
+        );
+        assert_eq!(wav_file.sub_chunks[2].chunk_id, [b'd', b'a', b't', b'a']);
+        assert_eq!(wav_file.sub_chunks[2].bytes_data_vec.len(), 1024);
+
+        let (_, decoded_channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+        let (_, decoded_data_channel_vec) = wav_file.get_audio_for_data_channel_vec().unwrap();
+        assert_eq!(decoded_data_channel_vec.len(), 1000);
+        assert_eq!(
+            decoded_data_channel_vec[999],
+            vec![decoded_channel_data_vec[0][999], decoded_channel_data_vec[1][999]]
+        );
+        for (decoded_data_vec, data_vec) in decoded_channel_data_vec.iter().zip(&channel_data_vec) {
+            assert_eq!(decoded_data_vec.len(), 1000);
+            // Signal to noise ratio is more than 30dB after the step size is adapted.
+            let error_power = decoded_data_vec
+                .iter()
+                .zip(data_vec)
+                .skip(100)
+                .map(|(decoded_val, val)| (decoded_val - val).powi(2))
+                .sum::<f64>()
+                / 900.0;
+            assert!(error_power < 0.125 / 1000.0);
+        }
+
+        let mut file_buf: Vec<u8> = Vec::new();
+        wav_file.write_to(&mut file_buf).unwrap();
+        assert_eq!(WavFile::from_bytes(&file_buf).unwrap(), wav_file);
+
+        // Block align of the options
+        let options = EncodeOptions {
+            op_block_align: Some(40),
+            ..Default::default()
+        };
+        wav_file
+            .update_audio_for_channel_data_vec_with_options(&wave_format, &channel_data_vec, &options)
+            .unwrap();
+        let fmt_chunk_body = &wav_file.sub_chunks[0].bytes_data_vec;
+        // 33 samples per block
+        assert_eq!(fmt_chunk_body[0x0c..0x14], [0x28, 0x00, 0x04, 0x00, 0x02, 0x00, 0x21, 0x00]);
+        // ceil(1000 / 33) blocks
+        assert_eq!(wav_file.sub_chunks[2].bytes_data_vec.len(), 31 * 40);
+        assert_eq!(wav_file.get_audio_for_channel_data_vec().unwrap().1[0].len(), 1000);
+        // Sample count of "ds64" chunk is the number of samples, not the number of blocks.
+        let mut rf64_wav_file = wav_file.clone();
+        rf64_wav_file.container = WavContainer::Rf64;
+        let rf64_buf = rf64_wav_file.to_bytes().unwrap();
+        assert_eq!(rf64_buf[0x24..0x2c], 1000u64.to_le_bytes());
+        rf64_wav_file.delete_sub_chunk([b'f', b'a', b'c', b't']);
+        let rf64_buf = rf64_wav_file.to_bytes().unwrap();
+        assert_eq!(rf64_buf[0x24..0x2c], (31u64 * 33).to_le_bytes());
+        let options = EncodeOptions {
+            op_block_align: Some(44),
+            ..Default::default()
+        };
+        assert!(wav_file
+            .update_audio_for_channel_data_vec_with_options(&wave_format, &channel_data_vec, &options)
+            .is_err());
+
+        // Streaming is not supported.
+        assert!(WavWriter::new(std::io::Cursor::new(Vec::new()), &wave_format).is_err());
+        assert!(WavReader::new(std::io::Cursor::new(file_buf)).is_err());
+    }
+
+    #[test]
+    fn ms_adpcm_test() {
+        // mono, 8000Hz, 8 bytes per block, 4 samples per block, 2 coefficients
+        let mut format_chunk_body: Vec<u8> = vec![0x02, 0x00, 0x01, 0x00, 0x40, 0x1f, 0x00, 0x00];
+        format_chunk_body.append(&mut vec![
+            0x80, 0x3e, 0x00, 0x00, 0x08, 0x00, 0x04, 0x00, 0x0c, 0x00, 0x04, 0x00,
+        ]);
+        format_chunk_body.append(&mut vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00]);
+        let mut data_chunk_body: Vec<u8> = vec![
+            // header: predictor 1, delta 16, sample1 100, sample2 90
+            0x01, 0x10, 0x00, 0x64, 0x00, 0x5a, 0x00, // codes: 1, -1
+            0x1f,
+        ];
+        let mut wav_file = WavFile::new();
+        for (chunk_id, bytes_data_vec) in [
+            ([b'f', b'm', b't', b' '], format_chunk_body),
+            ([b'd', b'a', b't', b'a'], data_chunk_body.clone()),
+        ] {
+            wav_file
+                .update_sub_chunk(SubChunk {
+                    chunk_id,
+                    bytes_data_vec,
+                })
+                .unwrap();
+        }
+        let (wave_format, channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+        assert_eq!(
+            wave_format,
+            WaveFormat {
+                id: WAVEFORMAT_ID_MS_ADPCM,
+                channel: 1,
+                sampling_rate: 8000,
+                bits: 4,
+            }
+        );
+        let pcm_vec: Vec<i64> = channel_data_vec[0]
+            .iter()
+            .map(|val| (val * BIT16_WAVE_DIVISOR as f64) as i64)
+            .collect();
+        assert_eq!(pcm_vec, vec![90, 100, 116, 100]);
+        // Predictor index out of the coefficients
+        data_chunk_body[0] = 0x02;
+        let data_chunk_idx = wav_file.get_sub_chunk_idx([b'd', b'a', b't', b'a']).unwrap();
+        wav_file.sub_chunks[data_chunk_idx].bytes_data_vec = data_chunk_body.clone();
+        assert!(wav_file.get_audio_for_channel_data_vec().is_err());
+        // The prediction of the extreme coefficients and samples exceeds 32bit.
+        wav_file.sub_chunks[0].bytes_data_vec[0x1a..0x1e].copy_from_slice(&[0x00, 0x80, 0x00, 0x80]);
+        data_chunk_body[0] = 0x01;
+        data_chunk_body[3..7].copy_from_slice(&[0x00, 0x80, 0x00, 0x80]);
+        wav_file.sub_chunks[data_chunk_idx].bytes_data_vec = data_chunk_body;
+        let pcm_vec: Vec<i64> = wav_file.get_audio_for_channel_data_vec().unwrap().1[0]
+            .iter()
+            .map(|val| (val * BIT16_WAVE_DIVISOR as f64) as i64)
+            .collect();
+        assert_eq!(pcm_vec[0..3], [-32768, -32768, 32767]);
+
+        // Encode
+        let wave_format = WaveFormat {
+            id: WAVEFORMAT_ID_MS_ADPCM,
+            channel: 2,
+            sampling_rate: 22050,
+            bits: 4,
+        };
+        let channel_data_vec: Vec<Vec<f64>> = (0..2)
+            .map(|channel_idx| {
+                (0..2000)
+                    .map(|idx| 0.5 * (2.0 * std::f64::consts::PI * 440.0 * (idx + channel_idx * 10) as f64 / 22050.0).sin())
+                    .collect()
+            })
+            .collect();
+        let data_channel_vec: Vec<Vec<f64>> = (0..2000)
+            .map(|idx| vec![channel_data_vec[0][idx], channel_data_vec[1][idx]])
+            .collect();
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let mut data_wav_file = WavFile::new();
+        data_wav_file
+            .update_audio_for_data_channel_vec(&wave_format, &data_channel_vec)
+            .unwrap();
+        assert_eq!(data_wav_file, wav_file);
+
+        // "fmt" chunk: 1024 bytes per block, 1012 samples per block, the standard coefficients
+        let mut expected_format_chunk_body: Vec<u8> =
+            vec![0x02, 0x00, 0x02, 0x00, 0x22, 0x56, 0x00, 0x00, 0x27, 0x57, 0x00, 0x00];
+        expected_format_chunk_body.append(&mut vec![0x00, 0x04, 0x04, 0x00, 0x20, 0x00, 0xf4, 0x03, 0x07, 0x00]);
+        for coefficient in [[256, 0], [512, -256], [0, 0], [192, 64], [240, 0], [460, -208], [392, -232]] {
+            for val in coefficient {
+                expected_format_chunk_body.append(&mut (val as i16).to_le_bytes().to_vec());
+            }
+        }
+        assert_eq!(wav_file.sub_chunks[0].bytes_data_vec, expected_format_chunk_body);
+        assert_eq!(
+            wav_file.sub_chunks[1],
+            SubChunk {
+                chunk_id: [b'f', b'a', b'c', b't'],
+                bytes_data_vec: vec![0xd0, 0x07, 0x00, 0x00],
+            }
+        );
+        assert_eq!(wav_file.sub_chunks[2].bytes_data_vec.len(), 2 * 1024);
+
+        let (_, decoded_channel_data_vec) = wav_file.get_audio_for_channel_data_vec().unwrap();
+        for (decoded_data_vec, data_vec) in decoded_channel_data_vec.iter().zip(&channel_data_vec) {
+            assert_eq!(decoded_data_vec.len(), 2000);
+            // The first 2 samples of each block are not compressed.
+            assert_eq!(
+                decoded_data_vec[1012],
+                (data_vec[1012] * BIT16_WAVE_DIVISOR as f64).round() / BIT16_WAVE_DIVISOR as f64
+            );
+            // Signal to noise ratio is more than 30dB after the delta is adapted.
+            let error_power = decoded_data_vec
+                .iter()
+                .zip(data_vec)
+                .skip(100)
+                .map(|(decoded_val, val)| (decoded_val - val).powi(2))
+                .sum::<f64>()
+                / 1900.0;
+            assert!(error_power < 0.125 / 1000.0);
+        }
+
+        let mut file_buf: Vec<u8> = Vec::new();
+        wav_file.write_to(&mut file_buf).unwrap();
+        assert_eq!(WavFile::from_bytes(&file_buf).unwrap(), wav_file);
+    }
+
+    #[test]
+    fn wave64_test() {
+        // 3 frames: "data" chunk body is 12 bytes and padded to 16 bytes.
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50], vec![0.25, -0.25, 0.00]];
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 8000,
+            bits: 16,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        wav_file.container = WavContainer::Wave64;
+        let file_buf = wav_file.to_bytes().unwrap();
+        // 40 = header, 24 + 16 = "fmt" chunk, 24 + 12 + 4 = "data" chunk
+        assert_eq!(file_buf.len(), 40 + 40 + 40);
+        // "riff" GUID, file size and "wave" GUID
+        assert_eq!(
+            file_buf[0x00..0x10],
+            [0x72, 0x69, 0x66, 0x66, 0x2e, 0x91, 0xcf, 0x11, 0xa5, 0xd6, 0x28, 0xdb, 0x04, 0xc1, 0x00, 0x00]
+        );
+        assert_eq!(file_buf[0x10..0x18], 120u64.to_le_bytes());
+        assert_eq!(
+            file_buf[0x18..0x28],
+            [0x77, 0x61, 0x76, 0x65, 0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a]
+        );
+        // "fmt" chunk GUID and size
+        assert_eq!(
+            file_buf[0x28..0x38],
+            [0x66, 0x6d, 0x74, 0x20, 0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a]
+        );
+        assert_eq!(file_buf[0x38..0x40], 40u64.to_le_bytes());
+        // "data" chunk GUID and size
+        assert_eq!(file_buf[0x50..0x54], [b'd', b'a', b't', b'a']);
+        assert_eq!(file_buf[0x60..0x68], 36u64.to_le_bytes());
+        assert_eq!(file_buf[0x74..0x78], [0x00; 4]);
+
+        let w64_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(w64_wav_file, wav_file);
+        assert_eq!(w64_wav_file.to_bytes().unwrap(), file_buf);
+
+        // Streaming
+        let mut wav_writer =
+            WavWriter::new_with_container(std::io::Cursor::new(Vec::new()), &wave_format, WavContainer::Wave64).unwrap();
+        wav_writer.write_audio_for_channel_data_vec(&channel_data_vec).unwrap();
+        assert_eq!(wav_writer.get_container(), WavContainer::Wave64);
+        assert_eq!(wav_writer.finalize().unwrap().into_inner(), file_buf);
+        let mut wav_reader = WavReader::new(std::io::Cursor::new(&file_buf)).unwrap();
+        assert_eq!(wav_reader.get_container(), WavContainer::Wave64);
+        assert_eq!(wav_reader.get_frame_len(), 3);
+        assert_eq!(
+            wav_reader.read_audio_for_channel_data_vec(3).unwrap(),
+            wav_file.get_audio_for_channel_data_vec().unwrap().1
+        );
+
+        // "LIST" and "JUNK" have their own GUIDs. Other chunk ids are in the first 4 bytes of GUID.
+        for (chunk_id, guid_head) in [
+            ([b'L', b'I', b'S', b'T'], [0x6c, 0x69, 0x73, 0x74, 0x2f, 0x91, 0xcf, 0x11]),
+            ([b'J', b'U', b'N', b'K'], [0x6a, 0x75, 0x6e, 0x6b, 0xf3, 0xac, 0xd3, 0x11]),
+            ([b'b', b'e', b'x', b't'], [0x62, 0x65, 0x78, 0x74, 0xf3, 0xac, 0xd3, 0x11]),
+        ] {
+            let mut chunk_wav_file = wav_file.clone();
+            chunk_wav_file
+                .update_sub_chunk(SubChunk {
+                    chunk_id,
+                    bytes_data_vec: vec![0x01, 0x02, 0x03],
+                })
+                .unwrap();
+            let chunk_file_buf = chunk_wav_file.to_bytes().unwrap();
+            assert_eq!(chunk_file_buf.len(), 120 + 24 + 8);
+            assert_eq!(chunk_file_buf[0x78..0x80], guid_head);
+            assert_eq!(chunk_file_buf[0x88..0x90], 27u64.to_le_bytes());
+            assert_eq!(WavFile::from_bytes(&chunk_file_buf).unwrap(), chunk_wav_file);
+        }
+
+        // GUID without the RIFF equivalent ("marker") is kept with the GUID.
+        let marker_guid: [u8; 16] = [
+            0x56, 0x62, 0xf7, 0xab, 0x2d, 0x39, 0xd2, 0x11, 0x86, 0xc7, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a,
+        ];
+        let mut unknown_file_buf = file_buf.clone();
+        unknown_file_buf[0x10..0x18].copy_from_slice(&152u64.to_le_bytes());
+        unknown_file_buf.extend_from_slice(&marker_guid);
+        unknown_file_buf.extend_from_slice(&29u64.to_le_bytes());
+        unknown_file_buf.extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0x00, 0x00]);
+        let unknown_wav_file = WavFile::from_bytes(&unknown_file_buf).unwrap();
+        assert_eq!(unknown_wav_file.sub_chunks[2].chunk_id, W64_GUID_CHUNK_ID);
+        assert_eq!(unknown_wav_file.sub_chunks[2].bytes_data_vec[0..16], marker_guid);
+        assert_eq!(
+            unknown_wav_file.sub_chunks[2].bytes_data_vec[16..],
+            [0x01, 0x02, 0x03, 0x04, 0x05]
+        );
+        assert_eq!(unknown_wav_file.to_bytes().unwrap(), unknown_file_buf);
+        let mut wav_reader = WavReader::new(std::io::Cursor::new(&unknown_file_buf)).unwrap();
+        assert_eq!(wav_reader.read_audio_for_channel_data_vec(3).unwrap().len(), 2);
+
+        // Sizes which were not fixed
+        let mut unfixed_file_buf = file_buf.clone();
+        unfixed_file_buf[0x10..0x18].copy_from_slice(&[0x00; 8]);
+        unfixed_file_buf[0x60..0x68].copy_from_slice(&[0x00; 8]);
+        unfixed_file_buf.truncate(0x74);
+        assert!(WavFile::from_bytes(&unfixed_file_buf).is_err());
+        let (repaired_wav_file, warnings) = WavFile::from_bytes_lenient(&unfixed_file_buf).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(repaired_wav_file, wav_file);
+    }
+
+    #[test]
+    fn aiff_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50], vec![0.25, -0.25, -1.00]];
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 44100,
+            bits: 16,
+        };
+
+        // AIFF (big endian)
+        let mut aiff_file = AiffFile::new();
+        aiff_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let file_buf = aiff_file.to_bytes().unwrap();
+        // 12 = header, 8 + 18 = "COMM" chunk, 8 + 8 + 12 = "SSND" chunk
+        assert_eq!(file_buf.len(), 66);
+        assert_eq!(
+            file_buf[0x00..0x0c],
+            [b'F', b'O', b'R', b'M', 0x00, 0x00, 0x00, 0x3a, b'A', b'I', b'F', b'F']
+        );
+        assert_eq!(
+            file_buf[0x0c..0x26],
+            [
+                b'C', b'O', b'M', b'M', 0x00, 0x00, 0x00, 0x12, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x10, 0x40, 0x0e, 0xac,
+                0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+            ]
+        );
+        assert_eq!(
+            file_buf[0x26..0x42],
+            [
+                b'S', b'S', b'N', b'D', 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+                0x00, 0x40, 0x00, 0xe0, 0x00, 0xc0, 0x00, 0x80, 0x00,
+            ]
+        );
+        let read_aiff_file = AiffFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(read_aiff_file, aiff_file);
+        assert_eq!(read_aiff_file.get_format().unwrap(), Some(wave_format));
+        assert_eq!(
+            read_aiff_file.get_audio_for_channel_data_vec().unwrap(),
+            (wave_format, channel_data_vec.clone())
+        );
+        let data_channel_vec = read_aiff_file.get_audio_for_data_channel_vec().unwrap().1;
+        assert_eq!(
+            data_channel_vec,
+            vec![vec![0.00, 0.25], vec![0.50, -0.25], vec![-0.50, -1.00]]
+        );
+
+        // AIFC "sowt" (little endian) 24bit
+        let wave_format_24 = WaveFormat { bits: 24, ..wave_format };
+        let mut aiff_file = AiffFile::new();
+        aiff_file.container = AiffContainer::AifcSowt;
+        aiff_file
+            .update_audio_for_data_channel_vec(&wave_format_24, &data_channel_vec)
+            .unwrap();
+        let file_buf = aiff_file.to_bytes().unwrap();
+        assert_eq!(file_buf[0x08..0x0c], [b'A', b'I', b'F', b'C']);
+        assert_eq!(
+            file_buf[0x0c..0x18],
+            [b'F', b'V', b'E', b'R', 0x00, 0x00, 0x00, 0x04, 0xa2, 0x80, 0x51, 0x40]
+        );
+        assert_eq!(file_buf[0x1c..0x20], 0x26u32.to_be_bytes());
+        assert_eq!(file_buf[0x32..0x36], [b's', b'o', b'w', b't']);
+        assert_eq!(file_buf[0x36], 14);
+        assert_eq!(&file_buf[0x37..0x45], b"not compressed");
+        assert_eq!(file_buf[0x45], 0x00);
+        // Second sample of SSND is the first sample of the right channel.
+        assert_eq!(file_buf[0x59..0x5c], [0x00, 0x00, 0x20]);
+        let read_aiff_file = AiffFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(read_aiff_file.container, AiffContainer::AifcSowt);
+        assert_eq!(
+            read_aiff_file.get_audio_for_channel_data_vec().unwrap(),
+            (wave_format_24, channel_data_vec.clone())
+        );
+
+        // IEEE float is written as AIFC "fl32" or "fl64".
+        for (bits, compression_type) in [(32, b"fl32"), (64, b"fl64")] {
+            let float_format = WaveFormat {
+                id: 3,
+                bits,
+                ..wave_format
+            };
+            let mut aiff_file = AiffFile::new();
+            aiff_file
+                .update_audio_for_channel_data_vec(&float_format, &channel_data_vec)
+                .unwrap();
+            assert_eq!(aiff_file.container, AiffContainer::Aifc);
+            let file_buf = aiff_file.to_bytes().unwrap();
+            assert_eq!(file_buf[0x08..0x0c], [b'A', b'I', b'F', b'C']);
+            assert_eq!(&file_buf[0x32..0x36], compression_type);
+            let read_aiff_file = AiffFile::from_bytes(&file_buf).unwrap();
+            assert_eq!(read_aiff_file, aiff_file);
+            assert_eq!(
+                read_aiff_file.get_audio_for_channel_data_vec().unwrap(),
+                (float_format, channel_data_vec.clone())
+            );
+        }
+
+        // 8bit is signed and 12bit is left-justified in 16bit.
+        let mut aiff_buf: Vec<u8> = vec![b'F', b'O', b'R', b'M', 0x00, 0x00, 0x00, 0x30, b'A', b'I', b'F', b'F'];
+        aiff_buf.extend_from_slice(&[
+            b'C', b'O', b'M', b'M', 0x00, 0x00, 0x00, 0x12, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02,
+        ]);
+        // 22254.545454 Hz
+        aiff_buf.extend_from_slice(&[0x00, 0x08, 0x40, 0x0d, 0xad, 0xdd, 0x17, 0x45, 0xd1, 0x74, 0x5d, 0x17]);
+        aiff_buf.extend_from_slice(&[b'S', b'S', b'N', b'D', 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00]);
+        aiff_buf.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x40, 0xc0]);
+        let aiff_file = AiffFile::from_bytes(&aiff_buf).unwrap();
+        assert_eq!(
+            aiff_file.get_audio_for_channel_data_vec().unwrap(),
+            (
+                WaveFormat {
+                    id: 1,
+                    channel: 1,
+                    sampling_rate: 22255,
+                    bits: 8
+                },
+                vec![vec![0.50, -0.50]]
+            )
+        );
+        aiff_buf[0x1b] = 0x0c;
+        let aiff_file = AiffFile::from_bytes(&aiff_buf).unwrap();
+        assert_eq!(aiff_file.get_format().unwrap().unwrap().bits, 16);
+        assert!(aiff_file.get_audio_for_channel_data_vec().is_err());
+
+        // "COMM" fields which are not supported or exceed "SSND" chunk
+        for (pos, bytes, err_kind) in [
+            (0x14, vec![0x00, 0x00], WavF64VecErrorKind::FormatIsNotSupported),
+            (0x1a, vec![0x00, 0x00], WavF64VecErrorKind::FormatIsNotSupported),
+            (0x16, vec![0xff, 0xff, 0xff, 0xff], WavF64VecErrorKind::SubChunkSizeError),
+        ] {
+            let mut wrong_comm_buf = aiff_buf.clone();
+            wrong_comm_buf[pos..pos + bytes.len()].copy_from_slice(&bytes);
+            let aiff_file = AiffFile::from_bytes(&wrong_comm_buf).unwrap();
+            let err = aiff_file.get_audio_for_channel_data_vec().unwrap_err();
+            assert_eq!(err.err_kind(), Some(&err_kind));
+        }
+
+        // "RIFF" is not AIFF.
+        assert!(AiffFile::from_bytes(&WavFile::new().to_bytes().unwrap()).is_err());
+    }
+
+    #[test]
+    fn rifx_test() {
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.00, 0.50, -0.50], vec![0.25, -0.25, 0.00]];
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 8000,
+            bits: 16,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let riff_buf = wav_file.to_bytes().unwrap();
+        wav_file.container = WavContainer::Rifx;
+        let file_buf = wav_file.to_bytes().unwrap();
+        assert_eq!(file_buf.len(), riff_buf.len());
+        assert_eq!(
+            file_buf[0x00..0x0c],
+            [b'R', b'I', b'F', b'X', 0x00, 0x00, 0x00, 0x30, b'W', b'A', b'V', b'E']
+        );
+        // "fmt" chunk
+        assert_eq!(
+            file_buf[0x0c..0x24],
+            [
+                b'f', b'm', b't', b' ', 0x00, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x1f, 0x40, 0x00, 0x00, 0x7d,
+                0x00, 0x00, 0x04, 0x00, 0x10
+            ]
+        );
+        // "data" chunk
+        assert_eq!(
+            file_buf[0x24..0x38],
+            [
+                b'd', b'a', b't', b'a', 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x20, 0x00, 0x40, 0x00, 0xe0, 0x00, 0xc0, 0x00, 0x00,
+                0x00
+            ]
+        );
+        // Sub chunks have the same bodies as "RIFF".
+        let rifx_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(rifx_wav_file, wav_file);
+        assert_eq!(rifx_wav_file.sub_chunks, WavFile::from_bytes(&riff_buf).unwrap().sub_chunks);
+        assert_eq!(
+            rifx_wav_file.get_audio_for_channel_data_vec().unwrap(),
+            (wave_format, channel_data_vec.clone())
+        );
+        assert_eq!(rifx_wav_file.to_bytes().unwrap(), file_buf);
+
+        // Streaming
+        let mut wav_writer =
+            WavWriter::new_with_container(std::io::Cursor::new(Vec::new()), &wave_format, WavContainer::Rifx).unwrap();
+        wav_writer.write_audio_for_channel_data_vec(&channel_data_vec).unwrap();
+        assert_eq!(wav_writer.finalize().unwrap().into_inner(), file_buf);
+        let mut wav_reader = WavReader::new(std::io::Cursor::new(&file_buf)).unwrap();
+        assert_eq!(wav_reader.get_container(), WavContainer::Rifx);
+        assert_eq!(wav_reader.get_format(), wave_format);
+        assert_eq!(wav_reader.read_audio_for_channel_data_vec(3).unwrap(), channel_data_vec);
+
+        // WAVE_FORMAT_EXTENSIBLE, IEEE float and "fact" chunk
+        let float_format = WaveFormat {
+            id: 3,
+            channel: 3,
+            sampling_rate: 48000,
+            bits: 32,
+        };
+        let float_channel_data_vec = vec![vec![0.50, -0.25], vec![0.25, 0.00], vec![-1.00, 0.75]];
+        let mut wav_file = WavFile::new();
+        wav_file.container = WavContainer::Rifx;
+        wav_file
+            .update_audio_for_channel_data_vec(&float_format, &float_channel_data_vec)
+            .unwrap();
+        let file_buf = wav_file.to_bytes().unwrap();
+        // Format Tag, Channel and Extension Size
+        assert_eq!(file_buf[0x14..0x18], [0xff, 0xfe, 0x00, 0x03]);
+        assert_eq!(file_buf[0x24..0x26], [0x00, 0x16]);
+        // Sub Format GUID
+        assert_eq!(file_buf[0x2c..0x30], [0x00, 0x00, 0x00, 0x03]);
+        assert_eq!(
+            file_buf[0x3c..0x48],
+            [b'f', b'a', b'c', b't', 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02]
+        );
+        let rifx_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(rifx_wav_file, wav_file);
+        assert_eq!(
+            rifx_wav_file.get_audio_for_channel_data_vec().unwrap(),
+            (float_format, float_channel_data_vec)
+        );
+        let mut wav_reader = WavReader::new(std::io::Cursor::new(&file_buf)).unwrap();
+        assert_eq!(wav_reader.get_format(), float_format);
+        assert_eq!(
+            wav_reader.read_audio_for_data_channel_vec(2).unwrap(),
+            vec![vec![0.50, 0.25, -1.00], vec![-0.25, 0.00, 0.75]]
+        );
+
+        // "cue ", "smpl", "bext" and "LIST" ("adtl" and "INFO") chunks
+        let channel_data_vec: Vec<Vec<f64>> = vec![vec![0.25; 8], vec![-0.25; 8]];
+        let mut wav_file = WavFile::new();
+        wav_file.container = WavContainer::Rifx;
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let cue_point_vec = vec![CuePoint::new_point(1, 2, "Point"), CuePoint::new_region(2, 4, 4, "Region")];
+        wav_file.update_cue_points(&cue_point_vec).unwrap();
+        let mut smpl_chunk = SmplChunk::new();
+        smpl_chunk.loop_vec.push(SampleLoop {
+            cue_point_id: 2,
+            loop_type: SampleLoopType::PingPong,
+            start: 4,
+            end: 7,
+            ..Default::default()
+        });
+        wav_file.update_smpl(&smpl_chunk).unwrap();
+        let mut bext_chunk = BextChunk::new();
+        bext_chunk.time_reference = 0x0000_0001_0000_0002;
+        bext_chunk.loudness_value = -2300;
+        wav_file.update_bext(&bext_chunk).unwrap();
+        let mut info_list = InfoList::new();
+        info_list.set_title("Title");
+        wav_file.update_info_list(&info_list).unwrap();
+        let file_buf = wav_file.to_bytes().unwrap();
+        let smpl_chunk_pos = file_buf.windows(4).position(|chunk_id| chunk_id == b"smpl").unwrap();
+        // Number of loops, sampler data size and cue point id of the loop
+        assert_eq!(
+            file_buf[smpl_chunk_pos + 0x24..smpl_chunk_pos + 0x30],
+            [0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02]
+        );
+        let bext_chunk_pos = file_buf.windows(4).position(|chunk_id| chunk_id == b"bext").unwrap();
+        // Time reference (low, high) and version
+        assert_eq!(
+            file_buf[bext_chunk_pos + 0x15a..bext_chunk_pos + 0x164],
+            [0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02]
+        );
+        let info_entry_pos = file_buf.windows(4).position(|chunk_id| chunk_id == b"INAM").unwrap();
+        assert_eq!(
+            file_buf[info_entry_pos..info_entry_pos + 0x0e],
+            *b"INAM\x00\x00\x00\x06Title\x00"
+        );
+        let cue_chunk_pos = file_buf.windows(4).position(|chunk_id| chunk_id == b"cue ").unwrap();
+        assert_eq!(
+            file_buf[cue_chunk_pos..cue_chunk_pos + 0x24],
+            [
+                b'c', b'u', b'e', b' ', 0x00, 0x00, 0x00, 0x34, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                0x02, b'd', b'a', b't', b'a', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02
+            ]
+        );
+        let ltxt_entry_pos = file_buf.windows(4).position(|chunk_id| chunk_id == b"ltxt").unwrap();
+        assert_eq!(
+            file_buf[ltxt_entry_pos..ltxt_entry_pos + 0x14],
+            [
+                b'l', b't', b'x', b't', 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x04, b'r', b'g', b'n',
+                b' '
+            ]
+        );
+        let rifx_wav_file = WavFile::from_bytes(&file_buf).unwrap();
+        assert_eq!(rifx_wav_file, wav_file);
+        assert_eq!(rifx_wav_file.get_cue_points().unwrap(), cue_point_vec);
+        assert_eq!(rifx_wav_file.get_smpl().unwrap(), Some(smpl_chunk));
+        assert_eq!(rifx_wav_file.get_bext().unwrap(), Some(bext_chunk));
+        assert_eq!(rifx_wav_file.get_info_list().unwrap(), Some(info_list));
+        assert_eq!(rifx_wav_file.to_bytes().unwrap(), file_buf);
+        let wav_reader = WavReader::new(std::io::Cursor::new(&file_buf)).unwrap();
+        assert_eq!(
+            wav_reader.get_sub_chunks(),
+            &wav_file
+                .sub_chunks
+                .iter()
+                .filter(|sub_chunk| sub_chunk.chunk_id != [b'd', b'a', b't', b'a'])
+                .cloned()
+                .collect::<Vec<SubChunk>>()
+        );
+
+        // The cue points are fitted to the new audio data of RIFX.
+        let mut rifx_wav_file = rifx_wav_file;
+        rifx_wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.25; 6], vec![-0.25; 6]])
+            .unwrap();
+        let rifx_wav_file = WavFile::from_bytes(&rifx_wav_file.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            rifx_wav_file.get_cue_points().unwrap(),
+            vec![CuePoint::new_point(1, 2, "Point"), CuePoint::new_region(2, 4, 2, "Region")]
+        );
+    }
+
+    #[test]
+    fn info_list_test() {
+        let mut wav_file = WavFile::new();
+        assert_eq!(wav_file.get_info_list().unwrap(), None);
+
+        // "LIST" chunk of other list type is kept.
+        let adtl_chunk = SubChunk {
+            chunk_id: [b'L', b'I', b'S', b'T'],
+            bytes_data_vec: b"adtl".to_vec(),
+        };
+        wav_file.update_sub_chunk(adtl_chunk.clone()).unwrap();
+
+        let mut info_list = InfoList::new();
+        info_list.set_title("Title");
+        info_list.set_artist("Artiste \u{e9}");
+        info_list.set_comment("");
+        info_list.set(*b"IXYZ", "unknown");
+        info_list.set_title("New Title");
+        wav_file.update_info_list(&info_list).unwrap();
+        assert_eq!(wav_file.sub_chunks.len(), 2);
+        assert_eq!(wav_file.sub_chunks[0], adtl_chunk);
+        let mut expected_body: Vec<u8> = b"INFO".to_vec();
+        expected_body.extend_from_slice(b"INAM\x0a\x00\x00\x00New Title\x00");
+        expected_body.extend_from_slice(b"IART\x0b\x00\x00\x00Artiste \xc3\xa9\x00\x00");
+        expected_body.extend_from_slice(b"ICMT\x01\x00\x00\x00\x00\x00");
+        expected_body.extend_from_slice(b"IXYZ\x08\x00\x00\x00unknown\x00");
+        assert_eq!(wav_file.sub_chunks[1].bytes_data_vec, expected_body);
+
+        let read_info_list = WavFile::from_bytes(&wav_file.to_bytes().unwrap())
+            .unwrap()
+            .get_info_list()
+            .unwrap()
+            .unwrap();
+        assert_eq!(read_info_list, info_list);
+        assert_eq!(read_info_list.get_title(), Some("New Title"));
+        assert_eq!(read_info_list.get_artist(), Some("Artiste \u{e9}"));
+        assert_eq!(read_info_list.get_comment(), Some(""));
+        assert_eq!(read_info_list.get(*b"IXYZ"), Some("unknown"));
+        assert_eq!(read_info_list.get_genre(), None);
+
+        // Update the existing "INFO" list.
+        info_list.set(INFO_ID_GENRE, "Jazz");
+        assert!(info_list.remove(*b"IXYZ"));
+        assert!(!info_list.remove(*b"IXYZ"));
+        wav_file.update_info_list(&info_list).unwrap();
+        assert_eq!(wav_file.sub_chunks.len(), 2);
+        assert_eq!(wav_file.get_info_list().unwrap().unwrap().get_genre(), Some("Jazz"));
+
+        // Latin-1 text, missing pad byte at the end and extra nulls
+        let info_chunk = SubChunk {
+            chunk_id: [b'L', b'I', b'S', b'T'],
+            bytes_data_vec: b"INFOICRD\x05\x00\x00\x002024\x00\x00IART\x07\x00\x00\x00Andr\xe9\x00\x00".to_vec(),
+        };
+        let info_list = InfoList::from_sub_chunk(&info_chunk).unwrap();
+        assert_eq!(info_list.get_creation_date(), Some("2024"));
+        assert_eq!(info_list.get_artist(), Some("Andr\u{e9}"));
+        assert!(InfoList::from_sub_chunk(&adtl_chunk).is_err());
+        let mut truncated_chunk = info_chunk.clone();
+        truncated_chunk.bytes_data_vec.truncate(28);
+        assert!(InfoList::from_sub_chunk(&truncated_chunk).is_err());
+    }
+
+    #[test]
+    fn bext_test() {
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(
+                &WaveFormat {
+                    id: 1,
+                    channel: 1,
+                    sampling_rate: 48000,
+                    bits: 16,
+                },
+                &vec![vec![0.0; 4]],
+            )
+            .unwrap();
+        assert_eq!(wav_file.get_bext().unwrap(), None);
+        assert_eq!(wav_file.get_bext_timestamp().unwrap(), None);
+
+        let mut bext_chunk = BextChunk::new();
+        bext_chunk.description = "Take 1".to_string();
+        bext_chunk.originator = "wav_f64vec".to_string();
+        bext_chunk.originator_reference = "REF0001".to_string();
+        bext_chunk.origination_date = "2024-01-31".to_string();
+        bext_chunk.origination_time = "12-34-56".to_string();
+        // 10:00:00 + 100 samples
+        bext_chunk.set_timestamp(36000, 100, 48000);
+        bext_chunk.umid = [0x11; 64];
+        bext_chunk.loudness_value = -2300;
+        bext_chunk.loudness_range = 500;
+        bext_chunk.max_true_peak_level = -100;
+        bext_chunk.max_momentary_loudness = -1800;
+        bext_chunk.max_short_term_loudness = -2000;
+        bext_chunk.coding_history = "A=PCM,F=48000,W=16,M=mono\r\n".to_string();
+        wav_file.update_bext(&bext_chunk).unwrap();
+
+        let sub_chunk = bext_chunk.to_sub_chunk().unwrap();
+        let chunk_body = &sub_chunk.bytes_data_vec;
+        assert_eq!(chunk_body.len(), 602 + 27);
+        assert_eq!(chunk_body[0x100..0x10a], *b"wav_f64vec");
+        assert_eq!(chunk_body[0x140..0x152], *b"2024-01-3112-34-56");
+        assert_eq!(chunk_body[0x152..0x15a], 1_728_000_100u64.to_le_bytes());
+        assert_eq!(chunk_body[0x15a..0x15c], [0x02, 0x00]);
+        assert_eq!(chunk_body[0x19c..0x19e], (-2300i16).to_le_bytes());
+
+        let read_wav_file = WavFile::from_bytes(&wav_file.to_bytes().unwrap()).unwrap();
+        assert_eq!(read_wav_file.get_bext().unwrap(), Some(bext_chunk.clone()));
+        assert_eq!(read_wav_file.get_bext_timestamp().unwrap(), Some((36000, 100)));
+        assert_eq!(bext_chunk.get_timestamp(44100).unwrap(), (39183, 29800));
+        assert!(bext_chunk.get_timestamp(0).is_err());
+
+        // Version 0 and 1 have no UMID or loudness fields.
+        for version in [0, 1] {
+            let mut old_bext_chunk = bext_chunk.clone();
+            old_bext_chunk.version = version;
+            let read_bext_chunk = BextChunk::from_sub_chunk(&old_bext_chunk.to_sub_chunk().unwrap()).unwrap();
+            assert_eq!(read_bext_chunk.version, version);
+            assert_eq!(read_bext_chunk.time_reference, bext_chunk.time_reference);
+            assert_eq!(read_bext_chunk.umid, if version == 0 { [0x00; 64] } else { [0x11; 64] });
+            assert_eq!(read_bext_chunk.loudness_value, 0);
+            assert_eq!(read_bext_chunk.coding_history, bext_chunk.coding_history);
+        }
+
+        // Too long text and too short chunk
+        bext_chunk.origination_date = "2024-01-31T".to_string();
+        assert!(bext_chunk.to_sub_chunk().is_err());
+        assert!(BextChunk::from_sub_chunk(&SubChunk {
+            chunk_id: [b'b', b'e', b'x', b't'],
+            bytes_data_vec: vec![0x00; 601],
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn cue_points_test() {
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 1,
+            sampling_rate: 8000,
+            bits: 16,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.0; 100]])
+            .unwrap();
+        assert_eq!(wav_file.get_cue_points().unwrap(), Vec::new());
+
+        let mut cue_point_vec = vec![
+            CuePoint::new_point(1, 10, "Marker 1"),
+            CuePoint::new_region(2, 20, 30, "Region"),
+            CuePoint::new_point(3, 90, ""),
+        ];
+        cue_point_vec[2].note = "odd".to_string();
+        wav_file.update_cue_points(&cue_point_vec).unwrap();
+        assert_eq!(wav_file.get_cue_points().unwrap(), cue_point_vec);
+        assert!(cue_point_vec[1].is_region());
+
+        // "cue " chunk
+        let cue_chunk = &wav_file.sub_chunks[2];
+        assert_eq!(cue_chunk.chunk_id, [b'c', b'u', b'e', b' ']);
+        assert_eq!(cue_chunk.bytes_data_vec.len(), 4 + 24 * 3);
+        assert_eq!(
+            cue_chunk.bytes_data_vec[0x1c..0x34],
+            [
+                0x02, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, b'd', b'a', b't', b'a', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x14, 0x00, 0x00, 0x00
+            ]
+        );
+        // "LIST" "adtl" chunk
+        let mut expected_body: Vec<u8> = b"adtl".to_vec();
+        expected_body.extend_from_slice(b"labl\x0d\x00\x00\x00\x01\x00\x00\x00Marker 1\x00\x00");
+        expected_body.extend_from_slice(b"ltxt\x14\x00\x00\x00\x02\x00\x00\x00\x1e\x00\x00\x00rgn ");
+        expected_body.extend_from_slice(&[0x00; 8]);
+        expected_body.extend_from_slice(b"labl\x0b\x00\x00\x00\x02\x00\x00\x00Region\x00\x00");
+        expected_body.extend_from_slice(b"note\x08\x00\x00\x00\x03\x00\x00\x00odd\x00");
+        assert_eq!(wav_file.sub_chunks[3].chunk_id, [b'L', b'I', b'S', b'T']);
+        assert_eq!(wav_file.sub_chunks[3].bytes_data_vec, expected_body);
+        let read_wav_file = WavFile::from_bytes(&wav_file.to_bytes().unwrap()).unwrap();
+        assert_eq!(read_wav_file.get_cue_points().unwrap(), cue_point_vec);
+
+        // Replacing audio data keeps the cue points within it.
+        wav_file
+            .update_audio_for_data_channel_vec(&wave_format, &vec![vec![0.0]; 40])
+            .unwrap();
+        assert_eq!(
+            wav_file.get_cue_points().unwrap(),
+            vec![
+                CuePoint::new_point(1, 10, "Marker 1"),
+                CuePoint::new_region(2, 20, 20, "Region")
+            ]
+        );
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.0; 5]])
+            .unwrap();
+        assert_eq!(wav_file.get_cue_points().unwrap(), Vec::new());
+        assert_eq!(wav_file.sub_chunks.len(), 2);
+
+        // Unknown "adtl" entries are kept.
+        wav_file
+            .update_sub_chunk(SubChunk {
+                chunk_id: [b'L', b'I', b'S', b'T'],
+                bytes_data_vec: b"adtlfile\x04\x00\x00\x00\x01\x00\x00\x00".to_vec(),
+            })
+            .unwrap();
+        wav_file.update_cue_points(&[CuePoint::new_point(1, 0, "Start")]).unwrap();
+        assert_eq!(
+            &wav_file.sub_chunks[2].bytes_data_vec[0..16],
+            b"adtlfile\x04\x00\x00\x00\x01\x00\x00\x00"
+        );
+        assert_eq!(wav_file.get_cue_points().unwrap()[0].label, "Start");
+        assert!(wav_file
+            .update_cue_points(&[CuePoint::new_point(1, 0, ""), CuePoint::new_point(1, 1, "")])
+            .is_err());
+
+        // "ltxt" entries keep the purpose, the locale and the text.
+        let mut ltxt_entry: Vec<u8> = b"ltxt\x1a\x00\x00\x00\x02\x00\x00\x00\x1e\x00\x00\x00sel ".to_vec();
+        ltxt_entry.extend_from_slice(&[0x01, 0x00, 0x09, 0x00, 0x01, 0x00, 0xe4, 0x04]);
+        ltxt_entry.extend_from_slice(b"Verse\x00");
+        let mut adtl_chunk_body: Vec<u8> = b"adtl".to_vec();
+        adtl_chunk_body.extend_from_slice(&ltxt_entry);
+        adtl_chunk_body.extend_from_slice(b"ltxt\x14\x00\x00\x00\x03\x00\x00\x00\x0a\x00\x00\x00rgn ");
+        adtl_chunk_body.extend_from_slice(&[0x00; 8]);
+        wav_file
+            .update_sub_chunk(SubChunk {
+                chunk_id: [b'L', b'I', b'S', b'T'],
+                bytes_data_vec: adtl_chunk_body,
+            })
+            .unwrap();
+        wav_file
+            .update_cue_points(&[CuePoint::new_region(2, 0, 4, "Chorus")])
+            .unwrap();
+        ltxt_entry[0x0c..0x10].copy_from_slice(&[0x04, 0x00, 0x00, 0x00]);
+        let adtl_chunk_body = &wav_file.sub_chunks[2].bytes_data_vec;
+        assert_eq!(adtl_chunk_body[4..4 + ltxt_entry.len()], ltxt_entry);
+        assert_eq!(
+            adtl_chunk_body[4 + ltxt_entry.len()..],
+            *b"labl\x0b\x00\x00\x00\x02\x00\x00\x00Chorus\x00\x00"
+        );
+        assert_eq!(
+            wav_file.get_cue_points().unwrap(),
+            vec![CuePoint::new_region(2, 0, 4, "Chorus")]
+        );
+
+        // Broken "cue " chunk is kept, and does not stop replacing audio data.
+        let broken_cue_chunk = SubChunk {
+            chunk_id: [b'c', b'u', b'e', b' '],
+            bytes_data_vec: vec![0x05, 0x00, 0x00, 0x00],
+        };
+        wav_file.update_sub_chunk(broken_cue_chunk.clone()).unwrap();
+        assert!(wav_file.get_cue_points().is_err());
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.5; 3]])
+            .unwrap();
+        assert_eq!(
+            wav_file.get_audio_for_channel_data_vec().unwrap(),
+            (wave_format, vec![vec![0.5; 3]])
+        );
+        assert!(wav_file.sub_chunks.contains(&broken_cue_chunk));
+    }
+
+    #[test]
+    fn smpl_inst_test() {
+        let wave_format = WaveFormat {
+            id: 1,
+            channel: 1,
+            sampling_rate: 44100,
+            bits: 16,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &vec![vec![0.0; 100]])
+            .unwrap();
+        assert_eq!(wav_file.get_smpl().unwrap(), None);
+        assert_eq!(wav_file.get_inst().unwrap(), None);
+
+        // "smpl" chunk
+        let mut smpl_chunk = SmplChunk::new();
+        smpl_chunk.sample_period = 22675;
+        smpl_chunk.midi_pitch_fraction = 0x80000000;
+        smpl_chunk.loop_vec = vec![
+            SampleLoop {
+                start: 10,
+                end: 49,
+                ..Default::default()
+            },
+            SampleLoop {
+                cue_point_id: 1,
+                loop_type: SampleLoopType::PingPong,
+                start: 50,
+                end: 79,
+                play_count: 2,
+                ..Default::default()
+            },
+            SampleLoop {
+                loop_type: SampleLoopType::Reverse,
+                start: 80,
+                end: 99,
+                ..Default::default()
+            },
+            SampleLoop {
+                loop_type: SampleLoopType::Other(32),
+                ..Default::default()
+            },
+        ];
+        smpl_chunk.sampler_data = vec![0x01, 0x02, 0x03];
+        wav_file.update_smpl(&smpl_chunk).unwrap();
+        let sub_chunk = &wav_file.sub_chunks[2];
+        assert_eq!(sub_chunk.chunk_id, [b's', b'm', b'p', b'l']);
+        assert_eq!(sub_chunk.bytes_data_vec.len(), 36 + 24 * 4 + 3);
+        assert_eq!(
+            sub_chunk.bytes_data_vec[0x0c..0x24],
+            [
+                0x3c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00,
+                0x00, 0x03, 0x00, 0x00, 0x00
+            ]
+        );
+        assert_eq!(
+            sub_chunk.bytes_data_vec[0x3c..0x54],
+            [
+                0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00, 0x4f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x02, 0x00, 0x00, 0x00
+            ]
+        );
+        assert_eq!(wav_file.get_smpl().unwrap(), Some(smpl_chunk.clone()));
+
+        // "inst" chunk
+        let inst_chunk = InstChunk {
+            unshifted_note: 69,
+            fine_tune: -12,
+            gain: -3,
+            low_note: 64,
+            high_note: 72,
+            low_velocity: 1,
+            high_velocity: 100,
+        };
+        wav_file.update_inst(&inst_chunk).unwrap();
+        assert_eq!(
+            wav_file.sub_chunks[3].bytes_data_vec,
+            vec![0x45, 0xf4, 0xfd, 0x40, 0x48, 0x01, 0x64]
+        );
+        let read_wav_file = WavFile::from_bytes(&wav_file.to_bytes().unwrap()).unwrap();
+        assert_eq!(read_wav_file.get_smpl().unwrap(), Some(smpl_chunk));
+        assert_eq!(read_wav_file.get_inst().unwrap(), Some(inst_chunk));
+        assert_eq!(InstChunk::new().high_velocity, 127);
+
+        // The number of loops which exceeds the chunk is an error.
+        wav_file.sub_chunks[2].bytes_data_vec[0x1c] = 0x05;
+        assert!(wav_file.get_smpl().is_err());
+    }
+
+    #[test]
+    fn fact_chunk_test() {
+        let float_format = WaveFormat {
+            id: 3,
+            channel: 2,
+            sampling_rate: 48000,
+            bits: 32,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&float_format, &vec![vec![0.0; 10], vec![0.0; 10]])
+            .unwrap();
+        // "fact" chunk is placed before "data" chunk.
+        assert_eq!(wav_file.sub_chunks[1].chunk_id, [b'f', b'a', b'c', b't']);
+        assert_eq!(wav_file.get_fact().unwrap(), Some(FactChunk::new(10)));
+
+        // A stale "fact" chunk is refreshed.
+        wav_file.update_fact(&FactChunk::new(1000)).unwrap();
+        wav_file
+            .update_audio_for_data_channel_vec(&float_format, &vec![vec![0.0, 0.0]; 4])
+            .unwrap();
+        assert_eq!(wav_file.get_fact().unwrap(), Some(FactChunk::new(4)));
+        let read_wav_file = WavFile::from_bytes(&wav_file.to_bytes().unwrap()).unwrap();
+        assert_eq!(read_wav_file.get_fact().unwrap(), Some(FactChunk::new(4)));
+
+        // PCM does not add "fact" chunk, but an existing one is refreshed.
+        let pcm_format = WaveFormat {
+            id: 1,
+            channel: 2,
+            sampling_rate: 48000,
+            bits: 16,
+        };
+        wav_file
+            .update_audio_for_channel_data_vec(&pcm_format, &vec![vec![0.0; 6], vec![0.0; 6]])
+            .unwrap();
+        assert_eq!(wav_file.get_fact().unwrap(), Some(FactChunk::new(6)));
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&pcm_format, &vec![vec![0.0; 6], vec![0.0; 6]])
+            .unwrap();
+        assert_eq!(wav_file.get_fact().unwrap(), None);
+
+        wav_file
+            .update_sub_chunk(SubChunk {
+                chunk_id: [b'f', b'a', b'c', b't'],
+                bytes_data_vec: vec![0x01, 0x00],
+            })
+            .unwrap();
+        assert!(wav_file.get_fact().is_err());
+
+        // The number of samples over 32bit is an error before any chunk is changed, except for RF64 which has it in "ds64" chunk.
+        let err = wav_file.make_fitted_fact_chunk(3, 0x1_0000_0000).unwrap_err();
+        assert_eq!(err.err_kind(), Some(&WavF64VecErrorKind::SubChunkSizeTooLarge));
+        wav_file.container = WavContainer::Rf64;
+        assert_eq!(
+            wav_file.make_fitted_fact_chunk(3, 0x1_0000_0000).unwrap(),
+            Some(FactChunk::new(0xffffffff))
+        );
+        assert_eq!(wav_file.make_fitted_fact_chunk(3, 10).unwrap(), Some(FactChunk::new(10)));
+    }
+
+    fn create_test_file(
+        id: usize,
+        channel: usize,
+        sampling_rate: usize,
+        bits: usize,
+        channel_data_vec: &Vec<Vec<f64>>,
+    ) -> PathBuf {
+        let wave_format = WaveFormat {
+            id: id,
+            channel: channel,
+            sampling_rate: sampling_rate,
+            bits: bits,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        let path_string = format!(
+            "./test_id{}_{}ch_{}hz_{}bits.wav",
+            wave_format.id, wave_format.channel, wave_format.sampling_rate, wave_format.bits
+        );
+        wav_file.save_as(Path::new(&path_string)).unwrap();
+        PathBuf::from(&path_string)
+    }
+
+    fn create_test_file_from_channel_vec(
+        id: usize,
+        channel: usize,
+        sampling_rate: usize,
+        bits: usize,
+        channel_data_vec: &Vec<Vec<f64>>,
+    ) -> PathBuf {
+        let wave_format = WaveFormat {
+            id: id,
+            channel: channel,
+            sampling_rate: sampling_rate,
+            bits: bits,
+        };
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_channel_data_vec(&wave_format, &channel_data_vec)
+            .unwrap();
+        wav_file.save_as(Path::new("./test_channel_vec.wav")).unwrap();
+        PathBuf::from(&"./test_channel_vec.wav")
+    }
+
+    fn create_test_file_from_data_vec(
+        id: usize,
+        channel: usize,
+        sampling_rate: usize,
+        bits: usize,
+        data_channel_vec: &Vec<Vec<f64>>,
+    ) -> PathBuf {
+        let wave_format = WaveFormat {
+            id: id,
+            channel: channel,
+            sampling_rate: sampling_rate,
+            bits: bits,
+        };
+
+        let mut wav_file = WavFile::new();
+        wav_file
+            .update_audio_for_data_channel_vec(&wave_format, &data_channel_vec)
+            .unwrap();
+        wav_file.save_as(Path::new("./test_data_vec.wav")).unwrap();
+        PathBuf::from(&"./test_data_vec.wav")
+    }
+
+    fn create_u8_vecs(
+        riff_chunk_size: &[u8],
+        fmt_chunk_format_id: &[u8],
+        fmt_chunk_channel: &[u8],
+        fmt_chunk_sampling_rate: &[u8],
+        fmt_chunk_bits: &[u8],
+        fmt_chunk_block_size: &[u8],
+        fmt_chunk_bit_rate: &[u8],
+        fmt_chunk_extension: &[u8],
+        data_chunk_size: &[u8],
+        data_chunk_data: &[u8],
+    ) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let riff_chunk_id: [u8; 4] = [0x52, 0x49, 0x46, 0x46];
+        let riff_chunk_wave: [u8; 4] = [0x57, 0x41, 0x56, 0x45];
+        let fmt_chunk_id: [u8; 4] = [0x66, 0x6D, 0x74, 0x20];
+        let fmt_chunk_size: [u8; 4] = (0x10 + fmt_chunk_extension.len() as u32).to_le_bytes();
+        let data_chunk_id: [u8; 4] = [0x64, 0x61, 0x74, 0x61];
+
+        let mut vec_of_fmt_chunk = fmt_chunk_format_id.to_vec();
+        vec_of_fmt_chunk.append(&mut fmt_chunk_channel.to_vec());
+        vec_of_fmt_chunk.append(&mut fmt_chunk_sampling_rate.to_vec());
+        vec_of_fmt_chunk.append(&mut fmt_chunk_bits.to_vec());
+        vec_of_fmt_chunk.append(&mut fmt_chunk_block_size.to_vec());
+        vec_of_fmt_chunk.append(&mut fmt_chunk_bit_rate.to_vec());
+        vec_of_fmt_chunk.append(&mut fmt_chunk_extension.to_vec());
+
+        let vec_of_data_chunk = data_chunk_data.to_vec();
+
+        let mut vec_of_file = riff_chunk_id.to_vec();
+        vec_of_file.append(&mut riff_chunk_size.to_vec());
+        vec_of_file.append(&mut riff_chunk_wave.to_vec());
+        vec_of_file.append(&mut fmt_chunk_id.to_vec());
+        vec_of_file.append(&mut fmt_chunk_size.to_vec());
+        vec_of_file.append(&mut vec_of_fmt_chunk.clone());
+        if fmt_chunk_format_id == [0x03, 0x00] {
+            // IEEE float has "fact" chunk with the number of samples of each channel.
+            let block_size = u16::from_le_bytes([fmt_chunk_block_size[0], fmt_chunk_block_size[1]]) as usize;
+            vec_of_file.append(&mut b"fact".to_vec());
+            vec_of_file.append(&mut vec![0x04, 0x00, 0x00, 0x00]);
+            vec_of_file.append(&mut ((data_chunk_data.len() / block_size) as u32).to_le_bytes().to_vec());
+        }
+        vec_of_file.append(&mut data_chunk_id.to_vec());
+        vec_of_file.append(&mut data_chunk_size.to_vec());
+        vec_of_file.append(&mut vec_of_data_chunk.clone());
+        if vec_of_data_chunk.len() % 2 == 1 {
+            vec_of_file.push(0x00);
+        }
 
         (vec_of_fmt_chunk, vec_of_data_chunk, vec_of_file)
     }
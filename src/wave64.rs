@@ -0,0 +1,74 @@
+/// "riff" GUID of Wave64. {66666972-912E-11CF-A5D6-28DB04C10000}
+pub(crate) const W64_RIFF_GUID: [u8; 16] = [
+    0x72, 0x69, 0x66, 0x66, 0x2e, 0x91, 0xcf, 0x11, 0xa5, 0xd6, 0x28, 0xdb, 0x04, 0xc1, 0x00, 0x00,
+];
+/// "list" GUID of Wave64. {7473696C-912F-11CF-A5D6-28DB04C10000}
+const W64_LIST_GUID: [u8; 16] = [
+    0x6c, 0x69, 0x73, 0x74, 0x2f, 0x91, 0xcf, 0x11, 0xa5, 0xd6, 0x28, 0xdb, 0x04, 0xc1, 0x00, 0x00,
+];
+/// "wave" GUID of Wave64. {65766177-ACF3-11D3-8CD1-00C04F8EDB8A}
+pub(crate) const W64_WAVE_GUID: [u8; 16] = [
+    0x77, 0x61, 0x76, 0x65, 0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a,
+];
+/// Last 12 bytes of the GUIDs which have a RIFF chunk id in the first 4 bytes. ("fmt ", "data", "fact", ...)
+const W64_CHUNK_GUID_SUFFIX: [u8; 12] = [0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a];
+
+/// Size of the header of Wave64.
+/// * 16 = "riff" GUID
+/// * 8 = file size
+/// * 16 = "wave" GUID
+pub(crate) const W64_HEADER_SIZE: u64 = 40;
+/// Size of the chunk head of Wave64.
+/// * 16 = chunk GUID
+/// * 8 = chunk size including the chunk head
+pub(crate) const W64_CHUNK_HEAD_SIZE: u64 = 24;
+
+/// Chunk id of a Wave64 chunk whose GUID has no RIFF chunk id (e.g. "marker", "summarylist" and "levl").
+/// The chunk body starts with the 16 bytes of the original GUID, and the GUID is written back to Wave64.
+pub const W64_GUID_CHUNK_ID: [u8; 4] = [b'g', b'u', b'i', b'd'];
+
+/// Convert from a RIFF chunk id to the Wave64 chunk GUID.
+pub(crate) fn chunk_id_to_guid(chunk_id: [u8; 4]) -> [u8; 16] {
+    match chunk_id {
+        [b'L', b'I', b'S', b'T'] => W64_LIST_GUID,
+        _ => {
+            let chunk_id = match chunk_id {
+                [b'J', b'U', b'N', b'K'] => [b'j', b'u', b'n', b'k'],
+                _ => chunk_id,
+            };
+            let mut guid = [0u8; 16];
+            guid[0..4].copy_from_slice(&chunk_id);
+            guid[4..16].copy_from_slice(&W64_CHUNK_GUID_SUFFIX);
+            guid
+        }
+    }
+}
+
+/// Convert from a Wave64 chunk GUID to the RIFF chunk id. If the GUID has no RIFF equivalent, returns None.
+pub(crate) fn guid_to_chunk_id(guid: &[u8; 16]) -> Option<[u8; 4]> {
+    if *guid == W64_LIST_GUID {
+        return Some([b'L', b'I', b'S', b'T']);
+    }
+    if guid[4..16] != W64_CHUNK_GUID_SUFFIX {
+        return None;
+    }
+    match guid[0..4] {
+        [b'j', b'u', b'n', b'k'] => Some([b'J', b'U', b'N', b'K']),
+        _ => Some([guid[0], guid[1], guid[2], guid[3]]),
+    }
+}
+
+/// Get the Wave64 chunk GUID and the chunk body to write. The chunk of `W64_GUID_CHUNK_ID` has the GUID in its body.
+pub(crate) fn w64_chunk_guid_and_body(chunk_id: [u8; 4], chunk_body: &[u8]) -> ([u8; 16], &[u8]) {
+    if chunk_id == W64_GUID_CHUNK_ID && chunk_body.len() >= 16 {
+        if let Ok(guid) = <[u8; 16]>::try_from(&chunk_body[0..16]) {
+            return (guid, &chunk_body[16..]);
+        }
+    }
+    (chunk_id_to_guid(chunk_id), chunk_body)
+}
+
+/// Size of the padding which aligns the chunk to 8 bytes.
+pub(crate) fn w64_padding_size(size: u64) -> u64 {
+    (8 - size % 8) % 8
+}
@@ -0,0 +1,315 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufWriter;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use crate::adpcm::*;
+use crate::error::*;
+use crate::rf64::*;
+use crate::rifx::*;
+use crate::wave64::*;
+use crate::{WavContainer, WavFile, WaveFormat, WaveFormatExtension};
+
+/// Streaming wav file writer.
+/// The header is written when the writer is created, and audio data is written block by block.
+/// RIFF size and "data" chunk size are fixed by `finalize()`. If the writer is dropped without `finalize()`, they are fixed as far as possible.
+pub struct WavWriter<W: Write + Seek> {
+    op_writer: Option<W>,
+    wave_format: WaveFormat,
+    container: WavContainer,
+    riff_head: u64,
+    ds64_size: u64,
+    format_size: u64,
+    data_size: u64,
+}
+
+impl WavWriter<BufWriter<File>> {
+    /// Create wav file and write the header.
+    pub fn create(file_path: &Path, wave_format: &WaveFormat) -> Result<WavWriter<BufWriter<File>>> {
+        WavWriter::new(BufWriter::new(File::create(file_path)?), wave_format)
+    }
+
+    /// Create wav file with the container and write the header.
+    pub fn create_with_container(
+        file_path: &Path,
+        wave_format: &WaveFormat,
+        container: WavContainer,
+    ) -> Result<WavWriter<BufWriter<File>>> {
+        WavWriter::new_with_container(BufWriter::new(File::create(file_path)?), wave_format, container)
+    }
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Create structure and write the header to the stream.
+    pub fn new(writer: W, wave_format: &WaveFormat) -> Result<WavWriter<W>> {
+        Self::new_with_container(writer, wave_format, WavContainer::Riff)
+    }
+
+    /// Create structure with the container and write the header to the stream.
+    /// `WavContainer::RiffOrRf64` reserves a "JUNK" chunk, which becomes "ds64" chunk if the data exceeds 4GB.
+    pub fn new_with_container(mut writer: W, wave_format: &WaveFormat, container: WavContainer) -> Result<WavWriter<W>> {
+        // ADPCM blocks can not be written frame by frame.
+        if is_adpcm_format(wave_format.id) {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("format id".to_string()),
+            ));
+        }
+        let format_buf = WavFile::set_format_with_required_extension(wave_format, &WaveFormatExtension::new(wave_format))?;
+        // Samples are written in whole bytes.
+        if !wave_format.bits.is_multiple_of(8) || Self::block_size(wave_format) == 0 {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::FormatIsNotSupported,
+                Some("block size".to_string()),
+            ));
+        }
+        let riff_head = writer.stream_position()?;
+        if container == WavContainer::Wave64 {
+            writer.write_all(&Self::w64_header(&format_buf))?;
+            return Ok(WavWriter {
+                op_writer: Some(writer),
+                wave_format: *wave_format,
+                container,
+                riff_head,
+                ds64_size: 0,
+                format_size: format_buf.len() as u64,
+                data_size: 0,
+            });
+        }
+        let mut buf: Vec<u8> = Vec::new();
+        match container {
+            WavContainer::Rf64 => buf.append(&mut [b'R', b'F', b'6', b'4'].to_vec()),
+            WavContainer::Bw64 => buf.append(&mut [b'B', b'W', b'6', b'4'].to_vec()),
+            WavContainer::Rifx => buf.append(&mut [b'R', b'I', b'F', b'X'].to_vec()),
+            _ => buf.append(&mut [b'R', b'I', b'F', b'F'].to_vec()),
+        }
+        // RIFF Size is fixed by finalize().
+        buf.append(&mut Self::size_field(container).to_vec());
+        buf.append(&mut [b'W', b'A', b'V', b'E'].to_vec());
+        let mut ds64_size: u64 = 0;
+        match container {
+            WavContainer::Rf64 | WavContainer::Bw64 => {
+                buf.append(&mut [b'd', b's', b'6', b'4'].to_vec());
+                buf.append(&mut DS64_CHUNK_MIN_SIZE.to_le_bytes()[0..4].to_vec());
+                // "ds64" chunk body is fixed by finalize().
+                buf.append(&mut [0x00; DS64_CHUNK_MIN_SIZE].to_vec());
+                ds64_size = 8 + DS64_CHUNK_MIN_SIZE as u64;
+            }
+            WavContainer::RiffOrRf64 => {
+                buf.append(&mut [b'J', b'U', b'N', b'K'].to_vec());
+                buf.append(&mut DS64_CHUNK_MIN_SIZE.to_le_bytes()[0..4].to_vec());
+                buf.append(&mut [0x00; DS64_CHUNK_MIN_SIZE].to_vec());
+                ds64_size = 8 + DS64_CHUNK_MIN_SIZE as u64;
+            }
+            _ => {}
+        }
+        buf.append(&mut [b'f', b'm', b't', b' '].to_vec());
+        if container == WavContainer::Rifx {
+            buf.append(&mut (format_buf.len() as u32).to_be_bytes().to_vec());
+            buf.append(&mut swap_format_chunk(&format_buf, false));
+        } else {
+            buf.append(&mut format_buf.len().to_le_bytes()[0..4].to_vec());
+            buf.append(&mut format_buf.clone());
+        }
+        buf.append(&mut [b'd', b'a', b't', b'a'].to_vec());
+        // "data" chunk size is fixed by finalize().
+        buf.append(&mut Self::size_field(container).to_vec());
+        writer.write_all(&buf)?;
+
+        Ok(WavWriter {
+            op_writer: Some(writer),
+            wave_format: *wave_format,
+            container,
+            riff_head,
+            ds64_size,
+            format_size: format_buf.len() as u64,
+            data_size: 0,
+        })
+    }
+
+    /// Make the header, "fmt" chunk and "data" chunk head of Wave64.
+    fn w64_header(format_buf: &[u8]) -> Vec<u8> {
+        let mut buf: Vec<u8> = Vec::new();
+        buf.append(&mut W64_RIFF_GUID.to_vec());
+        // File Size is fixed by finalize().
+        buf.append(&mut [0x00; 8].to_vec());
+        buf.append(&mut W64_WAVE_GUID.to_vec());
+        buf.append(&mut chunk_id_to_guid([b'f', b'm', b't', b' ']).to_vec());
+        buf.append(&mut (W64_CHUNK_HEAD_SIZE + format_buf.len() as u64).to_le_bytes().to_vec());
+        buf.append(&mut format_buf.to_vec());
+        buf.append(&mut vec![0x00; w64_padding_size(format_buf.len() as u64) as usize]);
+        buf.append(&mut chunk_id_to_guid([b'd', b'a', b't', b'a']).to_vec());
+        // "data" chunk size is fixed by finalize().
+        buf.append(&mut [0x00; 8].to_vec());
+        buf
+    }
+
+    /// Get the container. `WavContainer::RiffOrRf64` is resolved by the written size.
+    pub fn get_container(&self) -> WavContainer {
+        match self.container {
+            WavContainer::RiffOrRf64 if self.riff_size() > 0xffffffff => WavContainer::Rf64,
+            WavContainer::RiffOrRf64 => WavContainer::Riff,
+            container => container,
+        }
+    }
+
+    /// Get WaveFormat
+    pub fn get_format(&self) -> WaveFormat {
+        self.wave_format
+    }
+
+    /// Get the number of written frames (samples per channel).
+    pub fn get_frame_len(&self) -> u64 {
+        self.data_size.checked_div(Self::block_size(&self.wave_format)).unwrap_or(0)
+    }
+
+    fn block_size(wave_format: &WaveFormat) -> u64 {
+        (wave_format.channel * wave_format.bits / 8) as u64
+    }
+
+    /// Write audio data.
+    /// Parameters: channel_data_vec(`Vec<Vec<f64>>`): Outer is channel vec. Inner is data vec.
+    pub fn write_audio_for_channel_data_vec(&mut self, channel_data_vec: &Vec<Vec<f64>>) -> Result<()> {
+        let bytes_data_vec = WavFile::from_channel_data_vec(&self.wave_format, channel_data_vec)?;
+        self.write_bytes_audio(&bytes_data_vec)
+    }
+
+    /// Write audio data.
+    /// Parameters: data_channel_vec(`Vec<Vec<f64>>`): Outer is data vec. Inner is channel vec.
+    pub fn write_audio_for_data_channel_vec(&mut self, data_channel_vec: &Vec<Vec<f64>>) -> Result<()> {
+        if data_channel_vec.is_empty() {
+            return Ok(());
+        }
+        let bytes_data_vec = WavFile::from_data_channel_vec(&self.wave_format, data_channel_vec)?;
+        self.write_bytes_audio(&bytes_data_vec)
+    }
+
+    /// Fix RIFF size and "data" chunk size, and return the underlying writer.
+    pub fn finalize(mut self) -> Result<W> {
+        self.fix_sizes()?;
+        // The writer is taken only here.
+        Ok(self.op_writer.take().unwrap())
+    }
+
+    fn write_bytes_audio(&mut self, bytes_data_vec: &[u8]) -> Result<()> {
+        let new_data_size = self.data_size + bytes_data_vec.len() as u64;
+        if (self.container == WavContainer::Riff || self.container == WavContainer::Rifx)
+            && 4 + 8 + self.format_size + 8 + new_data_size + new_data_size % 2 > 0xffffffff
+        {
+            return Err(WavF64VecError::new(
+                WavF64VecErrorKind::SubChunkSizeTooLarge,
+                Some("data".to_string()),
+            ));
+        }
+        if let Some(writer) = &mut self.op_writer {
+            if self.container == WavContainer::Rifx {
+                let mut rifx_bytes_data_vec = bytes_data_vec.to_vec();
+                swap_sample_bytes(&self.wave_format, &mut rifx_bytes_data_vec)?;
+                writer.write_all(&rifx_bytes_data_vec)?;
+            } else {
+                writer.write_all(bytes_data_vec)?;
+            }
+        }
+        self.data_size = new_data_size;
+        Ok(())
+    }
+
+    fn size_field(container: WavContainer) -> [u8; 4] {
+        match container {
+            WavContainer::Rf64 | WavContainer::Bw64 => RF64_SIZE_PLACEHOLDER.to_le_bytes(),
+            _ => [0x00; 4],
+        }
+    }
+
+    fn riff_size(&self) -> u64 {
+        if self.container == WavContainer::Wave64 {
+            // File size including the header. Chunks are aligned to 8 bytes.
+            return W64_HEADER_SIZE
+                + W64_CHUNK_HEAD_SIZE
+                + self.format_size
+                + w64_padding_size(self.format_size)
+                + W64_CHUNK_HEAD_SIZE
+                + self.data_size
+                + w64_padding_size(self.data_size);
+        }
+        // 4 = "WAVE", 8 = chunk_id + body_size, odd-length "data" chunk is followed by a pad byte.
+        4 + self.ds64_size + 8 + self.format_size + 8 + self.data_size + self.data_size % 2
+    }
+
+    fn fix_w64_sizes(&mut self) -> Result<()> {
+        let riff_size = self.riff_size();
+        // 16 = "riff" GUID, 16 = chunk GUID
+        let riff_size_pos = self.riff_head + 16;
+        let data_size_pos =
+            self.riff_head + W64_HEADER_SIZE + W64_CHUNK_HEAD_SIZE + self.format_size + w64_padding_size(self.format_size) + 16;
+        if let Some(writer) = &mut self.op_writer {
+            writer.write_all(&vec![0x00; w64_padding_size(self.data_size) as usize])?;
+            let end_pos = writer.stream_position()?;
+            writer.seek(SeekFrom::Start(riff_size_pos))?;
+            writer.write_all(&riff_size.to_le_bytes())?;
+            writer.seek(SeekFrom::Start(data_size_pos))?;
+            writer.write_all(&(W64_CHUNK_HEAD_SIZE + self.data_size).to_le_bytes())?;
+            writer.seek(SeekFrom::Start(end_pos))?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn fix_sizes(&mut self) -> Result<()> {
+        let container = self.get_container();
+        if container == WavContainer::Wave64 {
+            return self.fix_w64_sizes();
+        }
+        let riff_size = self.riff_size();
+        // 12 = "RIFF" + RIFF Size + "WAVE", 8 = chunk_id + body_size
+        let ds64_pos = self.riff_head + 12;
+        let data_size_pos = ds64_pos + self.ds64_size + 8 + self.format_size + 4;
+        let ds64 = Ds64 {
+            riff_size,
+            data_size: self.data_size,
+            sample_count: self.get_frame_len(),
+            table: Vec::new(),
+        };
+        if let Some(writer) = &mut self.op_writer {
+            if self.data_size % 2 == 1 {
+                writer.write_all(&[0x00])?;
+            }
+            let end_pos = writer.stream_position()?;
+            if container == WavContainer::Riff {
+                writer.seek(SeekFrom::Start(self.riff_head + 4))?;
+                writer.write_all(&u32::try_from(riff_size)?.to_le_bytes())?;
+                writer.seek(SeekFrom::Start(data_size_pos))?;
+                writer.write_all(&u32::try_from(self.data_size)?.to_le_bytes())?;
+            } else if container == WavContainer::Rifx {
+                writer.seek(SeekFrom::Start(self.riff_head + 4))?;
+                writer.write_all(&u32::try_from(riff_size)?.to_be_bytes())?;
+                writer.seek(SeekFrom::Start(data_size_pos))?;
+                writer.write_all(&u32::try_from(self.data_size)?.to_be_bytes())?;
+            } else {
+                if self.container == WavContainer::RiffOrRf64 {
+                    // Promote "RIFF" to "RF64" and "JUNK" to "ds64".
+                    writer.seek(SeekFrom::Start(self.riff_head))?;
+                    writer.write_all(b"RF64")?;
+                    writer.write_all(&RF64_SIZE_PLACEHOLDER.to_le_bytes())?;
+                    writer.seek(SeekFrom::Start(ds64_pos))?;
+                    writer.write_all(b"ds64")?;
+                    writer.seek(SeekFrom::Start(data_size_pos))?;
+                    writer.write_all(&RF64_SIZE_PLACEHOLDER.to_le_bytes())?;
+                }
+                writer.seek(SeekFrom::Start(ds64_pos + 8))?;
+                writer.write_all(&ds64.to_chunk())?;
+            }
+            writer.seek(SeekFrom::Start(end_pos))?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Drop for WavWriter<W> {
+    fn drop(&mut self) {
+        // Best-effort. Errors can not be reported.
+        let _ = self.fix_sizes();
+    }
+}